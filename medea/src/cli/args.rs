@@ -1,8 +1,16 @@
-use std::io::{self, Read};
+use std::cell::{RefCell, RefMut};
+use std::error::Error;
+use std::io::{self, BufWriter, ErrorKind, Read, Write};
 
+use chrono::{DateTime, Utc};
 use clap::Parser;
 use enum_dispatch::enum_dispatch;
+use indoc::indoc;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use sha2::{Digest, Sha256};
 use super::ArgsEnum;
+use super::commands::timestamp::parse_timestamp;
 
 #[derive(Parser, Debug)]
 #[command(about, version)]
@@ -10,8 +18,69 @@ pub struct BaseArgs {
     #[arg(long, help = "Trim newline from end of output", default_value="false")]
     pub trim: bool,
 
+    #[arg(
+        long,
+        global = true,
+        help = "Seed for reproducible random output",
+        long_help = "Seed for reproducible random output. The same seed will always give the same output, across releases and platforms"
+    )]
+    pub seed: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        env = "MEDEA_NOW",
+        value_name = "TIMESTAMP",
+        help = "Use this time as the current time",
+        long_help = indoc!{"
+            Use this time as the current time in every command that reads the
+            clock. Accepts any timestamp the timestamp command can parse.
+        "}
+    )]
+    pub now: Option<String>,
+
     #[command(subcommand)]
     pub command: ArgsEnum,
+
+    #[arg(skip)]
+    pub rng: RefCell<Option<ChaCha20Rng>>,
+}
+
+impl BaseArgs {
+    // the current time, or the time given with --now
+    pub fn now(&self) -> Result<DateTime<Utc>, Box<dyn Error>> {
+        match &self.now {
+            Some(now) => parse_timestamp(now).map_err(|e| format!("invalid --now: {}", e).into()),
+            None => Ok(Utc::now()),
+        }
+    }
+
+    // the random number generator shared by every command. any seed is hashed
+    // down to the 32 bytes chacha20 expects, so the stream only changes if the
+    // seed does. rand samples usize ranges differently on 32 and 64 bit
+    // targets, so seeded code samples u32 or u64 ranges and casts instead
+    pub fn rng(&self) -> RefMut<'_, ChaCha20Rng> {
+        RefMut::map(self.rng.borrow_mut(), |rng| {
+            rng.get_or_insert_with(|| match &self.seed {
+                Some(seed) => ChaCha20Rng::from_seed(Sha256::digest(seed.as_bytes()).into()),
+                None => ChaCha20Rng::from_entropy(),
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+impl BaseArgs {
+    // seeded base args around a command that a test built by hand
+    pub fn fixture(command: ArgsEnum) -> Self {
+        BaseArgs {
+            trim: false,
+            seed: Some(String::from("fixture")),
+            now: None,
+            command,
+            rng: Default::default(),
+        }
+    }
 }
 
 fn get_input_from_stdin() -> String {
@@ -20,19 +89,34 @@ fn get_input_from_stdin() -> String {
     return message;
 }
 
+fn write_output(args: &BaseArgs, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+    args.command.run_to(args, get_input_from_stdin, out)?;
+    if !args.trim {
+        writeln!(out)?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
 pub fn run() -> Result<(), Box<dyn std::error::Error>>  {
     let args = BaseArgs::parse();
-    let result = &args.command.run(&args, get_input_from_stdin)?;
-    if args.trim {
-        print!("{}", result);
-    } else {
-        println!("{}", result);
+    let mut out = BufWriter::with_capacity(1 << 16, io::stdout().lock());
+    match write_output(&args, &mut out) {
+        // the reader went away, e.g. `medea uuid 1G | head`
+        Err(e) if e.downcast_ref::<io::Error>().is_some_and(|e| e.kind() == ErrorKind::BrokenPipe) => Ok(()),
+        result => result,
     }
-    Ok(())
 }
 
 
 #[enum_dispatch]
 pub trait Runnable {
     fn run(&self, base_args: &BaseArgs, get_input: impl Fn() -> String) -> Result<String, Box<dyn std::error::Error>>;
+
+    // writes the output as it is generated. commands that can produce large
+    // outputs override this to avoid holding all of it in memory
+    fn run_to(&self, base_args: &BaseArgs, get_input: impl Fn() -> String, out: &mut dyn std::io::Write) -> Result<(), Box<dyn std::error::Error>> {
+        out.write_all(self.run(base_args, get_input)?.as_bytes())?;
+        Ok(())
+    }
 }
\ No newline at end of file
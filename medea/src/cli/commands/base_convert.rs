@@ -22,7 +22,7 @@ use std::error::Error;
 use clap::{Parser, ValueEnum};
 use indoc::indoc;
 
-use crate::cli::{args::{Runnable, BaseArgs}, utils::{base64_utils, hex_utils, ascii_utils}};
+use crate::cli::{args::{Runnable, BaseArgs}, utils::{base64_utils, hex_utils, ascii_utils, base32_utils, base58_utils, literal_utils::{self, Language}}};
 
 #[derive(Parser, Debug, Clone)]
 #[command(
@@ -42,6 +42,9 @@ use crate::cli::{args::{Runnable, BaseArgs}, utils::{base64_utils, hex_utils, as
 
             # convert individual bytes from hex to binary
             medea base -f hex -t bin AB CD 01 23
+
+            # embed a hex key in a c source file
+            medea base -f hex -t c 2b7e151628aed2a6abf7158809cf4f3c
     "#}
 )]
 pub struct BaseConvertArgs {
@@ -82,6 +85,15 @@ pub struct BaseConvertArgs {
         default_value = "false"
     )]
     upper: bool,
+
+    #[arg(
+        short,
+        long,
+        default_value = "80",
+        value_name = "COLUMNS",
+        help = "Maximum line width of c, rust, go, python and dec-list output, 0 for no wrapping"
+    )]
+    width: usize,
 }
 
 #[derive(ValueEnum, Debug, Clone)]
@@ -90,7 +102,19 @@ enum Format {
     Hex,
     B64,
     Ascii,
-    Dec
+    Dec,
+    B32,
+    B58,
+    #[value(help = "C uint8_t array initializer, output only")]
+    C,
+    #[value(help = "Rust [u8; N] array, output only")]
+    Rust,
+    #[value(help = "Go []byte literal, output only")]
+    Go,
+    #[value(help = "Python bytes literal, output only")]
+    Python,
+    #[value(help = "Comma-separated list of decimal bytes, output only")]
+    DecList,
 }
 
 trait Converter {
@@ -424,14 +448,85 @@ impl Converter for HexConverter {
     }
 }
 
+struct B32Converter;
+impl Converter for B32Converter {
+    fn validate_string(&self, bytes: &[String]) -> Result<(), Box<dyn Error>> {
+        base32_utils::decode(&bytes.concat())?;
+        Ok(())
+    }
+
+    fn validate_bytes(&self, _: &[u8]) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    fn to_bytes(&self, bytes: &[String]) -> Vec<u8> {
+        base32_utils::decode(&bytes.concat()).unwrap_or_default()
+    }
+
+    fn to_string(&self, bytes: &[u8], _: bool) -> Vec<String> {
+        vec![base32_utils::encode(bytes, true)]
+    }
+}
+
+struct B58Converter;
+impl Converter for B58Converter {
+    fn validate_string(&self, bytes: &[String]) -> Result<(), Box<dyn Error>> {
+        base58_utils::decode(&bytes.concat())?;
+        Ok(())
+    }
+
+    fn validate_bytes(&self, _: &[u8]) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    fn to_bytes(&self, bytes: &[String]) -> Vec<u8> {
+        base58_utils::decode(&bytes.concat()).unwrap_or_default()
+    }
+
+    fn to_string(&self, bytes: &[u8], _: bool) -> Vec<String> {
+        vec![base58_utils::encode(bytes)]
+    }
+}
+
+struct LiteralConverter {
+    language: Language,
+    width: usize,
+    upper: bool,
+}
+impl Converter for LiteralConverter {
+    fn validate_string(&self, _: &[String]) -> Result<(), Box<dyn Error>> {
+        Err("source code literals are only supported as an output format".into())
+    }
+
+    fn validate_bytes(&self, _: &[u8]) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    fn to_bytes(&self, _: &[String]) -> Vec<u8> {
+        Vec::new()
+    }
+
+    fn to_string(&self, bytes: &[u8], _: bool) -> Vec<String> {
+        vec![literal_utils::format(self.language, self.width, self.upper, bytes)]
+    }
+}
+
 impl BaseConvertArgs {
     fn select_converter(&self, format: &Format) -> Box<dyn Converter> {
+        let literal = |language| Box::new(LiteralConverter { language, width: self.width, upper: self.upper });
         match format {
             Format::Ascii => Box::new(AsciiConverter),
             Format::Bin => Box::new(BinConverter),
             Format::Dec => Box::new(DecConverter),
             Format::B64 => Box::new(B64Converter),
             Format::Hex => Box::new(HexConverter),
+            Format::B32 => Box::new(B32Converter),
+            Format::B58 => Box::new(B58Converter),
+            Format::C => literal(Language::C),
+            Format::Rust => literal(Language::Rust),
+            Format::Go => literal(Language::Go),
+            Format::Python => literal(Language::Python),
+            Format::DecList => literal(Language::DecList),
         }
     }
 }
@@ -830,4 +925,35 @@ mod hex_convert_test {
         assert_eq!(result, expected_result);
     }
 
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod base_convert_args_test {
+    use clap::Parser;
+    use rstest::rstest;
+
+    use crate::cli::args::{BaseArgs, Runnable};
+
+    fn run(args: &[&str]) -> Result<String, Box<dyn std::error::Error>> {
+        let base_args = BaseArgs::try_parse_from([&["medea", "base"], args].concat())?;
+        base_args.command.run(&base_args, String::new)
+    }
+
+    #[rstest(args, expected_result,
+        case(&["-f", "hex", "-t", "b32", "666f6f626172"], "MZXW6YTBOI======"),
+        case(&["-f", "b32", "-t", "ascii", "MZXW6YTBOI======"], "foobar"),
+        case(&["-f", "hex", "-t", "b58", "0000287fb4cd"], "11233QC4"),
+        case(&["-f", "b58", "-t", "hex", "11233QC4"], "0000287fb4cd"),
+        case(&["-f", "hex", "-t", "rust", "-u", "abcd"], "let data: [u8; 2] = [\n    0xAB, 0xCD,\n];"),
+        case(&["-f", "hex", "-t", "dec-list", "-w", "6", "01", "02", "03"], "1, 2,\n3"),
+        case(&["-t", "python", "104", "105"], "data = (\n    b\"\\x68\\x69\"\n)"),
+    )]
+    fn will_convert_to_new_formats(args: &[&str], expected_result: &str) {
+        assert_eq!(run(args).unwrap(), expected_result);
+    }
+
+    #[test]
+    fn will_reject_literal_input() {
+        assert!(run(&["-f", "c", "-t", "hex", "0x01"]).is_err());
+    }
+}
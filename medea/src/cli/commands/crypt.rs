@@ -0,0 +1,289 @@
+use std::error::Error;
+
+use clap::{Parser, ValueEnum};
+use indoc::indoc;
+use rand::{Rng, RngCore};
+
+use crate::cli::{args::{Runnable, BaseArgs}, utils::crypt_utils};
+
+#[derive(Parser, Debug, Clone)]
+#[command(
+    about = "Generate and verify unix crypt() password hashes",
+    after_help = "See `medea help crypt` for details",
+    long_about = indoc!{"
+        Hash a password using one of the crypt(3) schemes found in
+        /etc/shadow and htpasswd files, or verify a password against
+        an existing hash. The scheme of an existing hash is detected
+        from its prefix.
+    "},
+    after_long_help = indoc!{r#"
+        Examples:
+            # generate a sha512-crypt hash
+            $ medea crypt --salt saltstring 'Hello world!'
+            $6$saltstring$svn8UoSVapNtMuq1ukKS4tPQd8iKwSMHWjl/O817G3uBnIFNjnQJuesI68u4OTLiBFdcbYEdFCoEOfaS35inz1
+
+            # generate an htpasswd line for nginx or apache
+            $ medea crypt -s apr1 --htpasswd admin 'my password'
+            admin:$apr1$vA9pQe2j$7mJ0ne6vbm3tdbbWnHJMR0
+
+            # verify a password against an existing hash
+            $ medea crypt --verify '$1$saltsalt$qjXMvbEw8oaL.CzflDtaK/' password
+            password is valid
+    "#}
+)]
+pub struct CryptArgs {
+    #[arg(help = "Password to be hashed or verified")]
+    password: String,
+
+    #[arg(
+        short,
+        long,
+        help = "Hashing scheme",
+        value_enum,
+        default_value = "sha512"
+    )]
+    scheme: Scheme,
+
+    #[arg(
+        long,
+        help = "Salt to hash with",
+        long_help = indoc!{"
+            Salt to hash with. Randomly generated if omitted. Salts longer
+            than 8 characters (md5, apr1) or 16 characters (sha256, sha512)
+            will be truncated.
+        "}
+    )]
+    salt: Option<String>,
+
+    #[arg(
+        short,
+        long,
+        help = "Number of rounds for sha256 and sha512 schemes",
+        long_help = indoc!{"
+            Number of rounds for sha256 and sha512 schemes. Will be clamped
+            to between 1000 and 999999999. If omitted, the default of 5000
+            is used and left out of the output.
+        "}
+    )]
+    rounds: Option<u32>,
+
+    #[arg(
+        long,
+        value_name = "HASH",
+        conflicts_with_all = ["scheme", "salt", "rounds"],
+        help = "Verify the password against an existing hash or htpasswd line"
+    )]
+    verify: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "USER",
+        conflicts_with = "verify",
+        help = "Output a complete `user:hash` htpasswd line"
+    )]
+    htpasswd: Option<String>,
+}
+
+#[derive(ValueEnum, Debug, Clone, PartialEq)]
+enum Scheme {
+    Md5,
+    Apr1,
+    Sha256,
+    Sha512,
+}
+
+struct CryptParameters {
+    scheme: Scheme,
+    salt: String,
+    rounds: Option<u32>,
+    hash: String,
+}
+
+impl CryptArgs {
+    fn generate_salt(scheme: &Scheme, rng: &mut dyn RngCore) -> String {
+        let length = match scheme {
+            Scheme::Md5 | Scheme::Apr1 => crypt_utils::MD5_MAX_SALT_LENGTH,
+            Scheme::Sha256 | Scheme::Sha512 => crypt_utils::SHA_MAX_SALT_LENGTH,
+        };
+        let alphabet = crypt_utils::salt_alphabet();
+        (0..length)
+            .map(|_| alphabet[rng.gen_range(0..alphabet.len() as u32) as usize] as char)
+            .collect()
+    }
+
+    fn crypt(password: &str, scheme: &Scheme, salt: &str, rounds: Option<u32>) -> String {
+        let password = password.as_bytes();
+        match scheme {
+            Scheme::Md5 => crypt_utils::md5_crypt(password, salt),
+            Scheme::Apr1 => crypt_utils::apr1_crypt(password, salt),
+            Scheme::Sha256 => crypt_utils::sha256_crypt(password, salt, rounds),
+            Scheme::Sha512 => crypt_utils::sha512_crypt(password, salt, rounds),
+        }
+    }
+
+    fn parse_hash(hash: &str) -> Result<CryptParameters, Box<dyn Error>> {
+        // accept a complete htpasswd line as well as a bare hash
+        let hash = match hash.split_once(':') {
+            Some((_, h)) => h.trim(),
+            None => hash.trim(),
+        };
+
+        let (scheme, rest) = if let Some(rest) = hash.strip_prefix("$1$") {
+            (Scheme::Md5, rest)
+        } else if let Some(rest) = hash.strip_prefix("$apr1$") {
+            (Scheme::Apr1, rest)
+        } else if let Some(rest) = hash.strip_prefix("$5$") {
+            (Scheme::Sha256, rest)
+        } else if let Some(rest) = hash.strip_prefix("$6$") {
+            (Scheme::Sha512, rest)
+        } else {
+            return Err("unrecognized hash scheme, expected one of $1$, $apr1$, $5$ or $6$".into());
+        };
+
+        let (rounds, rest) = match rest.strip_prefix("rounds=") {
+            Some(r) => {
+                let (rounds, rest) = r.split_once('$').ok_or("malformed hash: missing salt")?;
+                let rounds = rounds.parse::<u32>().map_err(|e| format!("malformed hash: invalid rounds: {}", e))?;
+                (Some(rounds), rest)
+            },
+            None => (None, rest),
+        };
+
+        let (salt, hash) = rest.split_once('$').ok_or("malformed hash: missing salt")?;
+        Ok(CryptParameters {
+            scheme,
+            salt: salt.to_string(),
+            rounds,
+            hash: hash.to_string(),
+        })
+    }
+
+    fn verify_input(&self, hash: &str) -> Result<String, Box<dyn Error>> {
+        let parameters = Self::parse_hash(hash)?;
+        let computed = Self::crypt(&self.password, &parameters.scheme, &parameters.salt, parameters.rounds);
+        let computed_hash = computed.rsplit('$').next().unwrap_or_default();
+
+        match computed_hash == parameters.hash {
+            true => Ok(String::from("password is valid")),
+            false => Err("password is not valid".into()),
+        }
+    }
+
+    fn hash_input(&self, rng: &mut dyn RngCore) -> Result<String, Box<dyn Error>> {
+        if self.rounds.is_some() && (self.scheme == Scheme::Md5 || self.scheme == Scheme::Apr1) {
+            return Err("rounds are only supported by the sha256 and sha512 schemes".into());
+        }
+
+        let salt = match &self.salt {
+            Some(s) => {
+                if let Some(c) = s.chars().find(|c| !crypt_utils::is_salt_character(*c)) {
+                    return Err(format!("unexpected character in salt: {:?}", c).into());
+                }
+                s.clone()
+            },
+            None => Self::generate_salt(&self.scheme, rng),
+        };
+
+        let hash = Self::crypt(&self.password, &self.scheme, &salt, self.rounds);
+        match &self.htpasswd {
+            Some(user) => {
+                if user.contains(':') {
+                    return Err("htpasswd user names cannot contain ':'".into());
+                }
+                Ok(format!("{}:{}", user, hash))
+            },
+            None => Ok(hash),
+        }
+    }
+}
+
+impl Runnable for CryptArgs {
+    fn run(&self, base_args: &BaseArgs, _: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        match &self.verify {
+            Some(hash) => self.verify_input(hash),
+            None => self.hash_input(&mut *base_args.rng()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use rstest::rstest;
+
+    use crate::cli::{
+        args::{BaseArgs, Runnable},
+        ArgsEnum,
+    };
+
+    use super::{CryptArgs, Scheme};
+
+    fn base_args(a: CryptArgs) -> BaseArgs {
+        BaseArgs::fixture(ArgsEnum::Crypt(a))
+    }
+
+    fn args(password: &str) -> CryptArgs {
+        CryptArgs {
+            password: String::from(password),
+            scheme: Scheme::Sha512,
+            salt: None,
+            rounds: None,
+            verify: None,
+            htpasswd: None,
+        }
+    }
+
+    #[test]
+    fn will_generate_htpasswd_line() {
+        let sut = CryptArgs {
+            scheme: Scheme::Apr1,
+            salt: Some(String::from("saltsalt")),
+            htpasswd: Some(String::from("admin")),
+            ..args("password")
+        };
+
+        let result = sut.run(&base_args(sut.clone()), String::new).unwrap();
+        assert_eq!(result, "admin:$apr1$saltsalt$yAAkm4libquA.ZWLHbSBq/");
+    }
+
+    #[rstest(scheme,
+        case(Scheme::Md5),
+        case(Scheme::Apr1),
+        case(Scheme::Sha256),
+        case(Scheme::Sha512),
+    )]
+    fn will_verify_generated_hash(scheme: Scheme) {
+        let sut = CryptArgs { scheme, ..args("password") };
+        let hash = sut.run(&base_args(sut.clone()), String::new).unwrap();
+
+        let sut = CryptArgs { verify: Some(hash), ..args("password") };
+        assert!(sut.run(&base_args(sut.clone()), String::new).is_ok());
+    }
+
+    #[rstest(hash,
+        case("$6$rounds=1000$abcdefgh$wuAp2XWwaaguzVxZjeM2bd1yLSqbC/I9sr9DFeOfIPoAZiIj3ecL6rf9ibuAg8RDmh1vqbaeL0NSLJtGPF7b60"),
+        case("admin:$1$saltsalt$qjXMvbEw8oaL.CzflDtaK/"),
+    )]
+    fn will_verify_existing_hash(hash: &str) {
+        let sut = CryptArgs { verify: Some(String::from(hash)), ..args("password") };
+        assert!(sut.run(&base_args(sut.clone()), String::new).is_ok());
+
+        let sut = CryptArgs { verify: Some(String::from(hash)), ..args("not the password") };
+        assert!(sut.run(&base_args(sut.clone()), String::new).is_err());
+    }
+
+    #[test]
+    fn will_reject_rounds_for_md5() {
+        let sut = CryptArgs { scheme: Scheme::Md5, rounds: Some(1000), ..args("password") };
+        assert!(sut.run(&base_args(sut.clone()), String::new).is_err());
+    }
+
+    #[test]
+    fn will_generate_same_salt_for_same_seed() {
+        let base_args = BaseArgs::try_parse_from(["medea", "--seed", "fixture", "crypt", "password"]).unwrap();
+        assert_eq!(
+            base_args.command.run(&base_args, String::new).unwrap(),
+            "$6$Htvk8yT/kOdTbkiy$wPznoYcJ3ZMKKtRKtmSKZw9SKEi4Fc0QSvzIWbFiHEOqD2iqUN1239bA6cEglANBESgWDXqZ2zMJvuBoc3mwt."
+        );
+    }
+}
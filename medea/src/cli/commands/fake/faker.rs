@@ -0,0 +1,389 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use clap::ValueEnum;
+use rand::{seq::SliceRandom, Rng};
+
+const LOREM: &str = include_str!("data/lorem.txt");
+
+const EMAIL_DOMAINS: [&str; 3] = ["example.com", "example.org", "example.net"];
+
+// {v} is replaced by a major version, {m} by a minor version
+const USER_AGENTS: [(&str, u32, u32); 9] = [
+    ("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/{v}.0.0.0 Safari/537.36", 110, 130),
+    ("Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/{v}.0.0.0 Safari/537.36", 110, 130),
+    ("Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/{v}.0.0.0 Safari/537.36", 110, 130),
+    ("Mozilla/5.0 (Linux; Android 10; K) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/{v}.0.0.0 Mobile Safari/537.36", 110, 130),
+    ("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/{v}.0.0.0 Safari/537.36 Edg/{v}.0.0.0", 110, 130),
+    ("Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:{v}.0) Gecko/20100101 Firefox/{v}.0", 110, 130),
+    ("Mozilla/5.0 (X11; Linux x86_64; rv:{v}.0) Gecko/20100101 Firefox/{v}.0", 110, 130),
+    ("Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/{v}.{m} Safari/605.1.15", 15, 17),
+    ("Mozilla/5.0 (iPhone; CPU iPhone OS {v}_{m} like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/{v}.{m} Mobile/15E148 Safari/604.1", 15, 17),
+];
+
+// brand, prefixes and length of each card number
+const CARD_BRANDS: [(&str, &[&str], usize); 4] = [
+    ("visa", &["4"], 16),
+    ("mastercard", &["51", "52", "53", "54", "55", "2221", "2720"], 16),
+    ("amex", &["34", "37"], 15),
+    ("discover", &["6011", "65"], 16),
+];
+
+// letters used in the inward part of uk postcodes
+const POSTCODE_LETTERS: &str = "ABDEFGHJLNPQRSTUWXYZ";
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    Name,
+    Email,
+    Username,
+    Phone,
+    Address,
+    Company,
+    Ipv4,
+    Ipv6,
+    Mac,
+    Url,
+    UserAgent,
+    CreditCard,
+    Lorem,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum Locale {
+    EnUs,
+    EnGb,
+    DeDe,
+}
+
+// one generated value, as named fields for json and csv output and as a
+// single line for plain output
+pub struct Record {
+    pub fields: Vec<(&'static str, String)>,
+    pub line: String,
+}
+
+impl Record {
+    fn single(name: &'static str, value: String) -> Record {
+        Record { fields: vec![(name, value.clone())], line: value }
+    }
+}
+
+pub struct Faker {
+    locale: Locale,
+    first_names: Vec<&'static str>,
+    last_names: Vec<&'static str>,
+    streets: Vec<&'static str>,
+    // city, then the region and postal code prefix where the locale has them
+    cities: Vec<Vec<&'static str>>,
+    lorem: Vec<&'static str>,
+}
+
+impl Faker {
+    pub fn new(locale: Locale) -> Faker {
+        let (first_names, last_names, streets, cities) = match locale {
+            Locale::EnUs => (
+                include_str!("data/en_us/first_names.txt"),
+                include_str!("data/en_us/last_names.txt"),
+                include_str!("data/en_us/streets.txt"),
+                include_str!("data/en_us/cities.txt"),
+            ),
+            Locale::EnGb => (
+                include_str!("data/en_gb/first_names.txt"),
+                include_str!("data/en_gb/last_names.txt"),
+                include_str!("data/en_gb/streets.txt"),
+                include_str!("data/en_gb/cities.txt"),
+            ),
+            Locale::DeDe => (
+                include_str!("data/de_de/first_names.txt"),
+                include_str!("data/de_de/last_names.txt"),
+                include_str!("data/de_de/streets.txt"),
+                include_str!("data/de_de/cities.txt"),
+            ),
+        };
+        Faker {
+            locale,
+            first_names: first_names.lines().collect(),
+            last_names: last_names.lines().collect(),
+            streets: streets.lines().collect(),
+            cities: cities.lines().map(|l| l.split(',').collect()).collect(),
+            lorem: LOREM.lines().collect(),
+        }
+    }
+
+    pub fn generate(&self, kind: Kind, rng: &mut impl Rng) -> Record {
+        match kind {
+            Kind::Name => {
+                let first_name = pick(&self.first_names, rng);
+                let last_name = pick(&self.last_names, rng);
+                let name = format!("{} {}", first_name, last_name);
+                Record {
+                    fields: vec![("name", name.clone()), ("first_name", first_name), ("last_name", last_name)],
+                    line: name,
+                }
+            },
+            Kind::Email => Record::single("email", self.email(rng)),
+            Kind::Username => Record::single("username", self.username(rng)),
+            Kind::Phone => Record::single("phone", self.phone(rng)),
+            Kind::Address => self.address(rng),
+            Kind::Company => Record::single("company", self.company(rng)),
+            Kind::Ipv4 => Record::single("ipv4", Ipv4Addr::from(rng.gen::<u32>()).to_string()),
+            Kind::Ipv6 => Record::single("ipv6", Ipv6Addr::from(rng.gen::<u128>()).to_string()),
+            Kind::Mac => Record::single("mac", mac(rng)),
+            Kind::Url => Record::single("url", self.url(rng)),
+            Kind::UserAgent => Record::single("user_agent", user_agent(rng)),
+            Kind::CreditCard => {
+                let (brand, number) = credit_card(rng);
+                Record { fields: vec![("number", number.clone()), ("brand", brand.to_string())], line: number }
+            },
+            Kind::Lorem => Record::single("lorem", self.paragraph(rng)),
+        }
+    }
+
+    pub fn username(&self, rng: &mut impl Rng) -> String {
+        let first = ascii_fold(&pick(&self.first_names, rng));
+        let last = ascii_fold(&pick(&self.last_names, rng));
+        match rng.gen_range(0..4) {
+            0 => format!("{}{}", &first[..1], last),
+            1 => format!("{}.{}", first, last),
+            2 => format!("{}_{}{}", first, last, rng.gen_range(1..100)),
+            _ => format!("{}{}", first, rng.gen_range(1950..2010)),
+        }
+    }
+
+    pub fn email(&self, rng: &mut impl Rng) -> String {
+        format!("{}@{}", self.username(rng), pick(&EMAIL_DOMAINS, rng))
+    }
+
+    // numbers in the ranges set aside for fiction where the locale has them,
+    // 555-0100 to 555-0199 in north america and ofcom's drama numbers in the uk
+    pub fn phone(&self, rng: &mut impl Rng) -> String {
+        match self.locale {
+            Locale::EnUs => format!(
+                "({}{}{}) 555-01{:02}",
+                rng.gen_range(2..10),
+                rng.gen_range(0..9),
+                rng.gen_range(0..10),
+                rng.gen_range(0..100)
+            ),
+            Locale::EnGb => match rng.gen_bool(0.5) {
+                true => format!("07700 900{:03}", rng.gen_range(0..1000)),
+                false => format!("020 7946 0{:03}", rng.gen_range(0..1000)),
+            },
+            Locale::DeDe => format!(
+                "0{} {}",
+                pick(&["30", "40", "69", "89", "211", "221", "341", "711"], rng),
+                rng.gen_range(1_000_000..100_000_000)
+            ),
+        }
+    }
+
+    fn address(&self, rng: &mut impl Rng) -> Record {
+        let street = pick(&self.streets, rng);
+        let city = self.cities.choose(rng).unwrap();
+        let (street, postal_code, country) = match self.locale {
+            Locale::EnUs => (
+                format!("{} {}", rng.gen_range(1..10000), street),
+                format!("{}{:02}", city[2], rng.gen_range(0..100)),
+                "United States",
+            ),
+            Locale::EnGb => {
+                let letters: Vec<char> = POSTCODE_LETTERS.chars().collect();
+                let postal_code = format!(
+                    "{}{} {}{}{}",
+                    city[1],
+                    rng.gen_range(1..21),
+                    rng.gen_range(0..10),
+                    letters.choose(rng).unwrap(),
+                    letters.choose(rng).unwrap()
+                );
+                (format!("{} {}", rng.gen_range(1..300), street), postal_code, "United Kingdom")
+            },
+            Locale::DeDe => (
+                format!("{} {}", street, rng.gen_range(1..200)),
+                format!("{}{:03}", city[2], rng.gen_range(0..1000)),
+                "Deutschland",
+            ),
+        };
+        let line = match self.locale {
+            Locale::EnUs => format!("{}, {}, {} {}", street, city[0], city[1], postal_code),
+            Locale::EnGb => format!("{}, {} {}", street, city[0], postal_code),
+            Locale::DeDe => format!("{}, {} {}", street, postal_code, city[0]),
+        };
+
+        let mut fields = vec![("address", line.clone()), ("street", street), ("city", city[0].to_string())];
+        if self.locale != Locale::EnGb {
+            fields.push(("region", city[1].to_string()));
+        }
+        fields.push(("postal_code", postal_code));
+        fields.push(("country", country.to_string()));
+        Record { fields, line }
+    }
+
+    pub fn company(&self, rng: &mut impl Rng) -> String {
+        let suffixes: &[&str] = match self.locale {
+            Locale::EnUs => &["Inc.", "LLC", "Corp.", "Group", "Holdings", "Partners", "Industries", "Labs"],
+            Locale::EnGb => &["Ltd", "PLC", "Group", "Holdings", "Partners", "& Sons"],
+            Locale::DeDe => &["GmbH", "AG", "GmbH & Co. KG", "KG", "Gruppe"],
+        };
+        let suffix = pick(suffixes, rng);
+        match rng.gen_bool(0.5) {
+            true => format!("{} {}", pick(&self.last_names, rng), suffix),
+            false => format!("{} & {} {}", pick(&self.last_names, rng), pick(&self.last_names, rng), suffix),
+        }
+    }
+
+    pub fn url(&self, rng: &mut impl Rng) -> String {
+        let host = ascii_fold(&pick(&self.last_names, rng));
+        let path: Vec<String> = (0..rng.gen_range(0..4)).map(|_| pick(&self.lorem, rng)).collect();
+        format!("https://{}.{}/{}", host, pick(&EMAIL_DOMAINS, rng), path.join("/"))
+    }
+
+    pub fn sentence(&self, rng: &mut impl Rng) -> String {
+        let words: Vec<String> = (0..rng.gen_range(6..15)).map(|_| pick(&self.lorem, rng)).collect();
+        let sentence = words.join(" ");
+        let mut chars = sentence.chars();
+        let first = chars.next().unwrap().to_uppercase();
+        format!("{}{}.", first, chars.as_str())
+    }
+
+    pub fn paragraph(&self, rng: &mut impl Rng) -> String {
+        let sentences: Vec<String> = (0..rng.gen_range(3..7)).map(|_| self.sentence(rng)).collect();
+        sentences.join(" ")
+    }
+}
+
+fn pick<T: ToString>(items: &[T], rng: &mut impl Rng) -> String {
+    items.choose(rng).unwrap().to_string()
+}
+
+// lower case ascii for usernames and hosts, spelling out umlauts the way
+// german does
+fn ascii_fold(input: &str) -> String {
+    let mut output = String::new();
+    for c in input.to_lowercase().chars() {
+        match c {
+            'ä' => output.push_str("ae"),
+            'ö' => output.push_str("oe"),
+            'ü' => output.push_str("ue"),
+            'ß' => output.push_str("ss"),
+            'é' | 'è' | 'ê' => output.push('e'),
+            'á' | 'à' | 'â' => output.push('a'),
+            c if c.is_ascii_alphanumeric() => output.push(c),
+            _ => {},
+        }
+    }
+    output
+}
+
+// a unicast, locally administered address, so it can't clash with a real
+// vendor's
+fn mac(rng: &mut impl Rng) -> String {
+    let mut bytes: [u8; 6] = rng.gen();
+    bytes[0] = (bytes[0] & 0xfe) | 0x02;
+    bytes.iter().map(|b| format!("{:02x}", b)).collect::<Vec<String>>().join(":")
+}
+
+fn user_agent(rng: &mut impl Rng) -> String {
+    let (template, min, max) = USER_AGENTS.choose(rng).unwrap();
+    template
+        .replace("{v}", &rng.gen_range(*min..=*max).to_string())
+        .replace("{m}", &rng.gen_range(0..7).to_string())
+}
+
+pub fn luhn_check_digit(digits: &str) -> u32 {
+    let sum: u32 = digits
+        .chars()
+        .rev()
+        .filter_map(|c| c.to_digit(10))
+        .enumerate()
+        .map(|(i, d)| match i % 2 {
+            0 => if d * 2 > 9 { d * 2 - 9 } else { d * 2 },
+            _ => d,
+        })
+        .sum();
+    (10 - sum % 10) % 10
+}
+
+fn credit_card(rng: &mut impl Rng) -> (&'static str, String) {
+    let (brand, prefixes, length) = CARD_BRANDS.choose(rng).unwrap();
+    let mut number = prefixes.choose(rng).unwrap().to_string();
+    while number.len() < length - 1 {
+        number.push(char::from(b'0' + rng.gen_range(0..10)));
+    }
+    let check_digit = luhn_check_digit(&number);
+    number.push(char::from_digit(check_digit, 10).unwrap());
+    (brand, number)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+    use rstest::rstest;
+
+    use super::{ascii_fold, luhn_check_digit, Faker, Kind, Locale};
+
+    #[rstest(input, expected_result,
+        case("7992739871", 3),
+        case("453201511283036", 6),
+        case("37828224631000", 5),
+    )]
+    fn will_compute_luhn_check_digit(input: &str, expected_result: u32) {
+        assert_eq!(luhn_check_digit(input), expected_result);
+    }
+
+    #[test]
+    fn will_generate_valid_credit_cards() {
+        let sut = Faker::new(Locale::EnUs);
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        for _ in 0..100 {
+            let number = sut.generate(Kind::CreditCard, &mut rng).line;
+            let (payload, check_digit) = number.split_at(number.len() - 1);
+            assert_eq!(luhn_check_digit(payload).to_string(), check_digit);
+            assert!(number.len() == 15 || number.len() == 16);
+        }
+    }
+
+    #[rstest(input, expected_result,
+        case("Müller", "mueller"),
+        case("O'Brien", "obrien"),
+        case("Weiß", "weiss"),
+    )]
+    fn will_fold_to_ascii(input: &str, expected_result: &str) {
+        assert_eq!(ascii_fold(input), expected_result);
+    }
+
+    #[rstest(locale, pattern,
+        case(Locale::EnUs, r"^\d{1,4} [A-Za-z. ]+, [A-Za-z. ]+, [A-Z]{2} \d{5}$"),
+        case(Locale::EnGb, r"^\d{1,3} [A-Za-z' ]+, [A-Za-z -]+ [A-Z]{1,2}\d{1,2} \d[A-Z]{2}$"),
+        case(Locale::DeDe, r"^[\w. ]+ \d{1,3}, \d{5} [\w ]+$"),
+    )]
+    fn will_generate_addresses(locale: Locale, pattern: &str) {
+        let sut = Faker::new(locale);
+        let matcher = regex::Regex::new(pattern).unwrap();
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        for _ in 0..100 {
+            let address = sut.generate(Kind::Address, &mut rng).line;
+            assert!(matcher.is_match(&address), "{}", address);
+        }
+    }
+
+    #[rstest(kind, pattern,
+        case(Kind::Email, r"^[a-z0-9._]+@example\.(com|org|net)$"),
+        case(Kind::Username, r"^[a-z0-9._]+$"),
+        case(Kind::Phone, r"^\([2-9]\d\d\) 555-01\d\d$"),
+        case(Kind::Mac, r"^[0-9a-f][26ae](:[0-9a-f]{2}){5}$"),
+        case(Kind::Url, r"^https://[a-z]+\.example\.(com|org|net)/[a-z/]*$"),
+        case(Kind::UserAgent, r"^Mozilla/5\.0 \("),
+        case(Kind::Lorem, r"^([A-Z][a-z ]+\. ?){3,6}$"),
+    )]
+    fn will_generate_kind(kind: Kind, pattern: &str) {
+        let sut = Faker::new(Locale::EnUs);
+        let matcher = regex::Regex::new(pattern).unwrap();
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        for _ in 0..100 {
+            let value = sut.generate(kind, &mut rng).line;
+            assert!(matcher.is_match(&value), "{}", value);
+        }
+    }
+}
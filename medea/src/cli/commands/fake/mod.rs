@@ -0,0 +1,217 @@
+use std::error::Error;
+use std::io::Write;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use enum_dispatch::enum_dispatch;
+use indoc::indoc;
+use serde_json::{Map, Value};
+
+use crate::cli::args::{Runnable, BaseArgs};
+use crate::cli::utils::size_utils;
+
+pub mod faker;
+pub mod schema;
+
+use faker::{Faker, Kind, Locale, Record};
+use schema::JsonArgs;
+
+#[derive(Parser, Debug, Clone)]
+#[command(
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true,
+    about = "Generate fake names, addresses, emails and more",
+    after_help = "See `medea help fake` for details",
+    long_about = indoc!{"
+        Generate fake data for seeding databases and tests. Names, streets and
+        cities come from datasets embedded in medea, so nothing is looked up
+        online. Emails and urls use the reserved example domains, and phone
+        numbers use the ranges set aside for fiction where the locale has
+        them, so the data never reaches real people.
+    "},
+    after_long_help = indoc!{r#"
+        Examples:
+            # generate 3 names
+            $ medea fake name -n 3
+            Deborah Reynolds
+            Kyle Hernandez
+            Grace Fisher
+
+            # generate german addresses as csv
+            $ medea fake address --locale de-de -t csv -n 2
+            address,street,city,region,postal_code,country
+            "Lindenstraße 12, 80331 München",Lindenstraße 12,München,Bayern,80331,Deutschland
+            "Am Markt 3, 04109 Leipzig",Am Markt 3,Leipzig,Sachsen,04109,Deutschland
+
+            # generate a credit card number with a valid check digit
+            $ medea fake credit-card -t json
+
+            # generate api payloads from a json schema
+            $ medea fake json --schema user.json -n 20
+    "#}
+)]
+pub struct FakeArgs {
+    #[arg(help = "Kind of data to generate", value_enum, required = true)]
+    kind: Option<Kind>,
+
+    #[arg(
+        short = 'n',
+        long,
+        default_value = "1",
+        help = "Number of records to generate",
+        long_help = "Number of records to generate. Accepts suffixes like 10k, 10M or 1Gi",
+        value_parser = size_utils::parse_size
+    )]
+    count: u64,
+
+    #[arg(short, long, help = "Locale of the data", value_enum, default_value = "en-us")]
+    locale: Locale,
+
+    #[arg(
+        short,
+        long,
+        help = "Output format",
+        value_enum,
+        default_value = "lines",
+        value_name = "FORMAT"
+    )]
+    to: Format,
+
+    #[command(subcommand)]
+    command: Option<FakeCommand>,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+#[enum_dispatch(Runnable)]
+enum FakeCommand {
+    Json(JsonArgs),
+}
+
+#[derive(ValueEnum, Debug, Clone)]
+enum Format {
+    Lines,
+    Json,
+    Csv,
+}
+
+pub fn csv_field(value: &str) -> String {
+    match value.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", value.replace('"', "\"\"")),
+        false => value.to_string(),
+    }
+}
+
+pub fn csv_row<'a>(values: impl Iterator<Item = &'a str>) -> String {
+    values.map(csv_field).collect::<Vec<String>>().join(",")
+}
+
+fn json_object(record: &Record) -> Value {
+    let map: Map<String, Value> = record
+        .fields
+        .iter()
+        .map(|(k, v)| (k.to_string(), Value::String(v.clone())))
+        .collect();
+    Value::Object(map)
+}
+
+impl Runnable for FakeArgs {
+    fn run(&self, base_args: &BaseArgs, get_input: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        if let Some(command) = &self.command {
+            return command.run(base_args, get_input);
+        }
+
+        let mut buffer = Vec::new();
+        self.run_to(base_args, get_input, &mut buffer)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+
+    fn run_to(&self, base_args: &BaseArgs, get_input: impl Fn() -> String, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+        let kind = match (&self.command, self.kind) {
+            (Some(command), _) => return command.run_to(base_args, get_input, out),
+            (None, Some(kind)) => kind,
+            (None, None) => return Err("no kind of data given".into()),
+        };
+        let faker = Faker::new(self.locale);
+        let mut rng = base_args.rng();
+
+        if let Format::Json = self.to {
+            let records: Vec<Value> = (0..self.count).map(|_| json_object(&faker.generate(kind, &mut *rng))).collect();
+            out.write_all(serde_json::to_string_pretty(&records)?.as_bytes())?;
+            return Ok(());
+        }
+
+        for i in 0..self.count {
+            let record = faker.generate(kind, &mut *rng);
+            let line = match self.to {
+                Format::Csv => csv_row(record.fields.iter().map(|(_, v)| v.as_str())),
+                _ => record.line,
+            };
+            if i == 0 {
+                if let Format::Csv = self.to {
+                    writeln!(out, "{}", csv_row(record.fields.iter().map(|(k, _)| *k)))?;
+                }
+            } else {
+                out.write_all(b"\n")?;
+            }
+            out.write_all(line.as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use rstest::rstest;
+
+    use crate::cli::args::{BaseArgs, Runnable};
+
+    fn run(args: &[&str]) -> Result<String, Box<dyn std::error::Error>> {
+        let base_args = BaseArgs::try_parse_from([&["medea", "--seed", "fixture", "fake"], args].concat())?;
+        base_args.command.run(&base_args, String::new)
+    }
+
+    #[rstest(kind,
+        case("name"), case("email"), case("username"), case("phone"), case("address"), case("company"),
+        case("ipv4"), case("ipv6"), case("mac"), case("url"), case("user-agent"), case("credit-card"), case("lorem"),
+    )]
+    fn will_generate_each_kind(kind: &str) {
+        let result = run(&[kind, "-n", "10"]).unwrap();
+        assert_eq!(result.lines().count(), 10);
+        assert!(result.lines().all(|l| !l.is_empty()));
+    }
+
+    #[test]
+    fn will_generate_same_records_for_same_seed() {
+        assert_eq!(run(&["name", "-n", "3"]).unwrap(), "Nicholas Delgado\nLisa Miller\nMarilyn Hernandez");
+    }
+
+    #[test]
+    fn will_output_json() {
+        let result: serde_json::Value = serde_json::from_str(&run(&["name", "-n", "3", "-t", "json"]).unwrap()).unwrap();
+        let records = result.as_array().unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(
+            records[0]["name"].as_str().unwrap(),
+            format!("{} {}", records[0]["first_name"].as_str().unwrap(), records[0]["last_name"].as_str().unwrap())
+        );
+    }
+
+    #[test]
+    fn will_output_csv() {
+        let result = run(&["address", "-n", "3", "-t", "csv", "-l", "de-de"]).unwrap();
+        let lines: Vec<&str> = result.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], "address,street,city,region,postal_code,country");
+        assert!(lines[1].starts_with('"'));
+        assert!(lines[1].ends_with(",Deutschland"));
+    }
+
+    #[rstest(input, expected_result,
+        case("plain", "plain"),
+        case("a, b", "\"a, b\""),
+        case("say \"hi\"", "\"say \"\"hi\"\"\""),
+    )]
+    fn will_quote_csv_fields(input: &str, expected_result: &str) {
+        assert_eq!(super::csv_field(input), expected_result);
+    }
+}
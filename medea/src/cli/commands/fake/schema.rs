@@ -0,0 +1,590 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+use chrono::{DateTime, Duration, Utc};
+use clap::{Parser, ValueEnum};
+use indoc::indoc;
+use rand::{seq::SliceRandom, Rng};
+use serde_json::{json, Map, Value};
+use uuid::Builder;
+
+use crate::cli::args::{Runnable, BaseArgs};
+use crate::cli::commands::timestamp::{self, format_timestamp};
+use crate::cli::utils::{regex_utils::RegexGenerator, size_utils};
+
+use super::faker::{Faker, Kind, Locale};
+
+// past this depth, optional properties are left out and arrays are kept to
+// their minimum size, so recursive schemas bottom out
+const SOFT_MAX_DEPTH: usize = 16;
+const HARD_MAX_DEPTH: usize = 64;
+
+#[derive(Parser, Debug, Clone)]
+#[command(
+    about = "Generate json documents that conform to a json schema",
+    long_about = indoc!{"
+        Generate random json documents that conform to a json schema. Types,
+        enum, const, string formats, patterns, lengths, numeric bounds,
+        multipleOf, required and optional properties, arrays, uniqueItems,
+        allOf, anyOf, oneOf and local $refs are supported. Optional
+        properties are included about half of the time.
+
+        String formats uuid, date-time, date, time, email, uri, hostname,
+        ipv4 and ipv6 are generated by medea's own generators. Any kind of
+        fake data, like name or phone, can be used as a format as well.
+    "},
+    after_long_help = indoc!{r#"
+        Examples:
+            $ cat user.json
+            {
+              "type": "object",
+              "required": ["id", "email", "age"],
+              "properties": {
+                "id": { "type": "string", "format": "uuid" },
+                "email": { "type": "string", "format": "email" },
+                "age": { "type": "integer", "minimum": 18, "maximum": 90 },
+                "name": { "type": "string", "format": "name" }
+              }
+            }
+
+            $ medea fake json --schema user.json -n 2
+            {"age":42,"email":"lori_kim35@example.net","id":"7b0d1f3e-5c2a-4e8b-9a6d-3f1c0e2b4a5d","name":"Paul Riley"}
+            {"age":27,"email":"gary1967@example.org","id":"c4e2a9f1-0b3d-4c7e-8f5a-1d6b2e9c0a37"}
+    "#}
+)]
+pub struct JsonArgs {
+    #[arg(short, long, value_name = "FILE", help = "Json schema to generate documents for. Use `-` to read from stdin")]
+    schema: PathBuf,
+
+    #[arg(
+        short = 'n',
+        long,
+        default_value = "1",
+        help = "Number of documents to generate",
+        long_help = "Number of documents to generate. Accepts suffixes like 10k, 10M or 1Gi",
+        value_parser = size_utils::parse_size
+    )]
+    count: u64,
+
+    #[arg(short, long, help = "Locale of fake data formats", value_enum, default_value = "en-us")]
+    locale: Locale,
+
+    #[arg(short, long, default_value = "false", help = "Pretty print each document")]
+    pretty: bool,
+}
+
+struct SchemaGenerator<'a> {
+    root: &'a Value,
+    faker: Faker,
+    now: DateTime<Utc>,
+    // compiled patterns, so each pattern in the schema is only parsed once
+    patterns: RefCell<HashMap<String, RegexGenerator>>,
+}
+
+fn number(schema: &Value, key: &str) -> Option<f64> {
+    schema.get(key).and_then(|v| v.as_f64())
+}
+
+fn size(schema: &Value, key: &str) -> Option<usize> {
+    schema.get(key).and_then(|v| v.as_u64()).map(|v| v as usize)
+}
+
+// a bound of a number, and whether it is exclusive
+type Bound = Option<(f64, bool)>;
+
+// draft 4 spells exclusive bounds as booleans next to minimum and maximum
+fn bounds(schema: &Value) -> (Bound, Bound) {
+    let bound = |inclusive: &str, exclusive: &str| match (number(schema, inclusive), schema.get(exclusive)) {
+        (_, Some(Value::Number(n))) => n.as_f64().map(|n| (n, true)),
+        (Some(n), Some(Value::Bool(true))) => Some((n, true)),
+        (Some(n), _) => Some((n, false)),
+        (None, _) => None,
+    };
+    (bound("minimum", "exclusiveMinimum"), bound("maximum", "exclusiveMaximum"))
+}
+
+// combines the keywords of two schemas, for allOf and for the branch picked
+// out of anyOf and oneOf
+fn merge(base: &Value, other: &Value) -> Value {
+    let mut merged = base.as_object().cloned().unwrap_or_default();
+    for (key, value) in other.as_object().into_iter().flatten() {
+        let combined = match (key.as_str(), merged.get(key), value) {
+            ("properties", Some(Value::Object(a)), Value::Object(b)) => {
+                let mut properties = a.clone();
+                for (name, schema) in b {
+                    let schema = match properties.get(name) {
+                        Some(existing) => merge(existing, schema),
+                        None => schema.clone(),
+                    };
+                    properties.insert(name.clone(), schema);
+                }
+                Value::Object(properties)
+            },
+            ("required", Some(Value::Array(a)), Value::Array(b)) => {
+                Value::Array(a.iter().chain(b.iter().filter(|r| !a.contains(r))).cloned().collect())
+            },
+            _ => value.clone(),
+        };
+        merged.insert(key.clone(), combined);
+    }
+    Value::Object(merged)
+}
+
+impl<'a> SchemaGenerator<'a> {
+    fn resolve(&self, reference: &str) -> Result<&'a Value, Box<dyn Error>> {
+        let pointer = reference
+            .strip_prefix('#')
+            .ok_or_else(|| format!("only local $refs are supported, found {:?}", reference))?;
+        self.root.pointer(pointer).ok_or_else(|| format!("$ref {:?} does not point to anything", reference).into())
+    }
+
+    fn generate(&self, schema: &Value, depth: usize, rng: &mut impl Rng) -> Result<Value, Box<dyn Error>> {
+        if depth > HARD_MAX_DEPTH {
+            return Err("schema recurses too deeply to generate a finite document".into());
+        }
+        let object = match schema {
+            Value::Bool(true) => return Ok(json!(self.faker.sentence(rng))),
+            Value::Bool(false) => return Err("schema `false` can't be satisfied".into()),
+            Value::Object(object) => object,
+            _ => return Err(format!("expected a schema, found {}", schema).into()),
+        };
+
+        if let Some(reference) = object.get("$ref").and_then(|r| r.as_str()) {
+            return self.generate(self.resolve(reference)?, depth + 1, rng);
+        }
+        if let Some(value) = object.get("const") {
+            return Ok(value.clone());
+        }
+        if let Some(values) = object.get("enum").and_then(|e| e.as_array()) {
+            return values.choose(rng).cloned().ok_or_else(|| "enum is empty".into());
+        }
+        if let Some(schemas) = object.get("allOf").and_then(|a| a.as_array()) {
+            let mut base = object.clone();
+            base.remove("allOf");
+            let mut merged = Value::Object(base);
+            for schema in schemas {
+                let schema = match schema.get("$ref").and_then(|r| r.as_str()) {
+                    Some(reference) => self.resolve(reference)?,
+                    None => schema,
+                };
+                merged = merge(&merged, schema);
+            }
+            return self.generate(&merged, depth + 1, rng);
+        }
+        for keyword in ["anyOf", "oneOf"] {
+            if let Some(schemas) = object.get(keyword).and_then(|a| a.as_array()) {
+                let mut base = object.clone();
+                base.remove(keyword);
+                let branch = schemas.choose(rng).ok_or_else(|| format!("{} is empty", keyword))?;
+                let branch = match branch.get("$ref").and_then(|r| r.as_str()) {
+                    Some(reference) => self.resolve(reference)?,
+                    None => branch,
+                };
+                return self.generate(&merge(&Value::Object(base), branch), depth + 1, rng);
+            }
+        }
+
+        let type_name = match object.get("type") {
+            Some(Value::String(t)) => t.clone(),
+            Some(Value::Array(types)) => types
+                .choose(rng)
+                .and_then(|t| t.as_str())
+                .ok_or("type must be a string or a list of strings")?
+                .to_string(),
+            Some(t) => return Err(format!("invalid type {}", t).into()),
+            None if object.contains_key("properties") || object.contains_key("required") => String::from("object"),
+            None if object.contains_key("items") || object.contains_key("prefixItems") => String::from("array"),
+            None if object.contains_key("minimum") || object.contains_key("maximum") => String::from("number"),
+            None => String::from("string"),
+        };
+
+        match type_name.as_str() {
+            "null" => Ok(Value::Null),
+            "boolean" => Ok(json!(rng.gen_bool(0.5))),
+            "integer" => self.integer(schema, rng),
+            "number" => self.number(schema, rng),
+            "string" => self.string(schema, rng),
+            "array" => self.array(schema, depth, rng),
+            "object" => self.object(schema, depth, rng),
+            t => Err(format!("unknown type {:?}", t).into()),
+        }
+    }
+
+    fn integer(&self, schema: &Value, rng: &mut impl Rng) -> Result<Value, Box<dyn Error>> {
+        let (min, max) = bounds(schema);
+        let min = min.map(|(n, exclusive)| if exclusive { n.floor() as i64 + 1 } else { n.ceil() as i64 });
+        let max = max.map(|(n, exclusive)| if exclusive { n.ceil() as i64 - 1 } else { n.floor() as i64 });
+        let (min, max) = match (min, max) {
+            (Some(min), Some(max)) => (min, max),
+            (Some(min), None) => (min, min.saturating_add(1000)),
+            (None, Some(max)) => (max.saturating_sub(1000).min(0), max),
+            (None, None) => (0, 1000),
+        };
+
+        let step = match number(schema, "multipleOf") {
+            Some(m) if m.fract() == 0.0 && m > 0.0 => m as i64,
+            Some(m) => return Err(format!("multipleOf {} is not supported for integers", m).into()),
+            None => 1,
+        };
+        let (low, high) = (min.div_euclid(step) + i64::from(min.rem_euclid(step) != 0), max.div_euclid(step));
+        if low > high {
+            return Err(format!("no integer between {} and {} is a multiple of {}", min, max, step).into());
+        }
+        Ok(json!(rng.gen_range(low..=high) * step))
+    }
+
+    fn number(&self, schema: &Value, rng: &mut impl Rng) -> Result<Value, Box<dyn Error>> {
+        let (min, max) = bounds(schema);
+        let ((min, min_exclusive), (max, max_exclusive)) = match (min, max) {
+            (Some(min), Some(max)) => (min, max),
+            (Some(min), None) => (min, (min.0 + 1000.0, false)),
+            (None, Some(max)) => (((max.0 - 1000.0).min(0.0), false), max),
+            (None, None) => ((0.0, false), (1000.0, false)),
+        };
+        if let Some(step) = number(schema, "multipleOf") {
+            let (mut low, mut high) = ((min / step).ceil() as i64, (max / step).floor() as i64);
+            // a multiple that lands exactly on an exclusive bound is out
+            if min_exclusive && low as f64 * step <= min {
+                low += 1;
+            }
+            if max_exclusive && high as f64 * step >= max {
+                high -= 1;
+            }
+            if low > high || step <= 0.0 {
+                return Err(format!("no number between {} and {} is a multiple of {}", min, max, step).into());
+            }
+            return Ok(json!(rng.gen_range(low..=high) as f64 * step));
+        }
+        if min == max && !min_exclusive && !max_exclusive {
+            return Ok(json!(min));
+        }
+        if min >= max {
+            return Err(format!("no number between {} and {}", min, max).into());
+        }
+        // a half-open range is already clear of an exclusive maximum, and
+        // hitting an exclusive minimum exactly is not worth a retry loop
+        let mut value = rng.gen_range(min..max);
+        if value == min && min_exclusive {
+            value = (min + max) / 2.0;
+        }
+        Ok(json!(value))
+    }
+
+    fn string(&self, schema: &Value, rng: &mut impl Rng) -> Result<Value, Box<dyn Error>> {
+        if let Some(format) = schema.get("format").and_then(|f| f.as_str()) {
+            if let Some(value) = self.format(format, rng)? {
+                return Ok(json!(value));
+            }
+        }
+        if let Some(pattern) = schema.get("pattern").and_then(|p| p.as_str()) {
+            let mut patterns = self.patterns.borrow_mut();
+            if !patterns.contains_key(pattern) {
+                patterns.insert(pattern.to_string(), RegexGenerator::new(pattern, 8, false)?);
+            }
+            return Ok(json!(patterns[pattern].generate(rng)?));
+        }
+
+        let max = size(schema, "maxLength").unwrap_or(size(schema, "minLength").unwrap_or(0).max(5) + 10);
+        let min = size(schema, "minLength").unwrap_or(max.min(5));
+        if min > max {
+            return Err(format!("minLength {} is greater than maxLength {}", min, max).into());
+        }
+        let length = rng.gen_range(min as u64..=max as u64) as usize;
+        let value: String = (0..length).map(|_| char::from(b'a' + rng.gen_range(0..26))).collect();
+        Ok(json!(value))
+    }
+
+    fn format(&self, format: &str, rng: &mut impl Rng) -> Result<Option<String>, Box<dyn Error>> {
+        // somewhere in the year leading up to now
+        let time = self.now - Duration::seconds(rng.gen_range(0..365 * 24 * 3600));
+        let value = match format {
+            "uuid" => Builder::from_random_bytes(rng.gen()).into_uuid().to_string(),
+            "date-time" => format_timestamp(&time, &timestamp::Format::Iso, &None)?,
+            "date" => time.format("%Y-%m-%d").to_string(),
+            "time" => time.format("%H:%M:%SZ").to_string(),
+            "email" | "idn-email" => self.faker.email(rng),
+            "uri" | "url" | "iri" => self.faker.url(rng),
+            "hostname" | "idn-hostname" => self.faker.url(rng).trim_start_matches("https://").split('/').next().unwrap().to_string(),
+            "ipv4" => self.faker.generate(Kind::Ipv4, rng).line,
+            "ipv6" => self.faker.generate(Kind::Ipv6, rng).line,
+            format => match Kind::from_str(format, true) {
+                Ok(kind) => self.faker.generate(kind, rng).line,
+                Err(_) => return Ok(None),
+            },
+        };
+        Ok(Some(value))
+    }
+
+    fn array(&self, schema: &Value, depth: usize, rng: &mut impl Rng) -> Result<Value, Box<dyn Error>> {
+        // tuples are prefixItems since 2020-12 and an array of items before
+        let (prefix, items) = match (schema.get("prefixItems"), schema.get("items")) {
+            (Some(Value::Array(prefix)), items) => (prefix.clone(), items.cloned()),
+            (_, Some(Value::Array(prefix))) => (prefix.clone(), schema.get("additionalItems").cloned()),
+            (_, items) => (Vec::new(), items.cloned()),
+        };
+        let items = items.unwrap_or(Value::Bool(true));
+
+        let min = size(schema, "minItems").unwrap_or(0).max(match items {
+            Value::Bool(false) => prefix.len(),
+            _ => 0,
+        });
+        let max = match items {
+            Value::Bool(false) => prefix.len(),
+            _ => size(schema, "maxItems").unwrap_or(min.max(prefix.len()).max(1) + 4),
+        };
+        if min > max {
+            return Err(format!("minItems {} is greater than the {} items allowed", min, max).into());
+        }
+        let length = match depth > SOFT_MAX_DEPTH {
+            true => min,
+            false => rng.gen_range(min as u64..=max as u64) as usize,
+        };
+
+        let unique = schema.get("uniqueItems").and_then(|u| u.as_bool()).unwrap_or(false);
+        let mut seen = HashSet::new();
+        let mut values = Vec::with_capacity(length);
+        for i in 0..length {
+            let item_schema = prefix.get(i).unwrap_or(&items);
+            let mut attempts = 0;
+            loop {
+                let value = self.generate(item_schema, depth + 1, rng)?;
+                if !unique || seen.insert(value.to_string()) {
+                    values.push(value);
+                    break;
+                }
+                attempts += 1;
+                if attempts == 100 {
+                    return Err("could not generate enough unique items".into());
+                }
+            }
+        }
+        Ok(Value::Array(values))
+    }
+
+    fn object(&self, schema: &Value, depth: usize, rng: &mut impl Rng) -> Result<Value, Box<dyn Error>> {
+        let empty = Map::new();
+        let properties = schema.get("properties").and_then(|p| p.as_object()).unwrap_or(&empty);
+        let required: Vec<&str> = schema
+            .get("required")
+            .and_then(|r| r.as_array())
+            .map(|r| r.iter().filter_map(|r| r.as_str()).collect())
+            .unwrap_or_default();
+
+        let mut object = Map::new();
+        for (name, property) in properties {
+            if required.contains(&name.as_str()) || (depth <= SOFT_MAX_DEPTH && rng.gen_bool(0.5)) {
+                object.insert(name.clone(), self.generate(property, depth + 1, rng)?);
+            }
+        }
+        for name in required {
+            if !object.contains_key(name) {
+                object.insert(name.to_string(), self.generate(&Value::Bool(true), depth + 1, rng)?);
+            }
+        }
+        Ok(Value::Object(object))
+    }
+}
+
+impl JsonArgs {
+    fn read_schema(&self, get_input: impl Fn() -> String) -> Result<Value, Box<dyn Error>> {
+        let schema = match self.schema.to_str() {
+            Some("-") => get_input(),
+            _ => fs::read_to_string(&self.schema).map_err(|e| format!("{}: {}", self.schema.display(), e))?,
+        };
+        Ok(serde_json::from_str(&schema).map_err(|e| format!("invalid schema: {}", e))?)
+    }
+}
+
+impl Runnable for JsonArgs {
+    fn run(&self, base_args: &BaseArgs, get_input: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        let mut buffer = Vec::new();
+        self.run_to(base_args, get_input, &mut buffer)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+
+    fn run_to(&self, base_args: &BaseArgs, get_input: impl Fn() -> String, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+        let schema = self.read_schema(get_input)?;
+        let generator = SchemaGenerator {
+            root: &schema,
+            faker: Faker::new(self.locale),
+            now: base_args.now()?,
+            patterns: RefCell::new(HashMap::new()),
+        };
+        let mut rng = base_args.rng();
+        // documents are serialized into a buffer, since serde_json would wrap
+        // a write error and hide a closed pipe from the caller
+        let mut buffer = Vec::new();
+        for i in 0..self.count {
+            if i > 0 {
+                out.write_all(b"\n")?;
+            }
+            let document = generator.generate(&schema, 0, &mut *rng)?;
+            buffer.clear();
+            match self.pretty {
+                true => serde_json::to_writer_pretty(&mut buffer, &document)?,
+                false => serde_json::to_writer(&mut buffer, &document)?,
+            }
+            out.write_all(&buffer)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use rstest::rstest;
+    use serde_json::{json, Value};
+
+    use crate::cli::args::{BaseArgs, Runnable};
+
+    fn run(schema: Value, count: &str) -> Result<Vec<Value>, Box<dyn std::error::Error>> {
+        let base_args = BaseArgs::try_parse_from(["medea", "--seed", "fixture", "--now", "2024-01-01T00:00:00Z", "fake", "json", "-s", "-", "-n", count])?;
+        let output = base_args.command.run(&base_args, || schema.to_string())?;
+        Ok(output.lines().map(|l| serde_json::from_str(l).unwrap()).collect())
+    }
+
+    #[test]
+    fn will_generate_objects() {
+        let schema = json!({
+            "type": "object",
+            "required": ["id", "email", "age", "role", "created"],
+            "properties": {
+                "id": { "type": "string", "format": "uuid" },
+                "email": { "type": "string", "format": "email" },
+                "age": { "type": "integer", "minimum": 18, "exclusiveMaximum": 90 },
+                "role": { "enum": ["admin", "user"] },
+                "created": { "type": "string", "format": "date-time" },
+                "nickname": { "type": "string", "minLength": 3, "maxLength": 3 }
+            }
+        });
+        let documents = run(schema, "50").unwrap();
+        assert_eq!(documents.len(), 50);
+        for document in &documents {
+            assert!(uuid::Uuid::parse_str(document["id"].as_str().unwrap()).is_ok());
+            assert!(document["email"].as_str().unwrap().contains('@'));
+            assert!((18..90).contains(&document["age"].as_i64().unwrap()));
+            assert!(["admin", "user"].contains(&document["role"].as_str().unwrap()));
+            assert!(document["created"].as_str().unwrap().starts_with("202"));
+            if let Some(nickname) = document.get("nickname") {
+                assert_eq!(nickname.as_str().unwrap().len(), 3);
+            }
+        }
+        assert!(documents.iter().any(|d| d.get("nickname").is_some()));
+        assert!(documents.iter().any(|d| d.get("nickname").is_none()));
+    }
+
+    #[test]
+    fn will_generate_arrays_and_refs() {
+        let schema = json!({
+            "$defs": {
+                "tag": { "type": "string", "pattern": "^[a-z]{2}-\\d$" },
+                "node": {
+                    "type": "object",
+                    "required": ["value"],
+                    "properties": {
+                        "value": { "type": "number", "minimum": 0, "maximum": 1, "multipleOf": 0.25 },
+                        "children": { "type": "array", "items": { "$ref": "#/$defs/node" } }
+                    }
+                }
+            },
+            "type": "array",
+            "minItems": 3,
+            "maxItems": 5,
+            "uniqueItems": true,
+            "items": { "anyOf": [{ "$ref": "#/$defs/tag" }, { "$ref": "#/$defs/node" }] }
+        });
+        let matcher = regex::Regex::new(r"^[a-z]{2}-\d$").unwrap();
+        for document in run(schema, "20").unwrap() {
+            let items = document.as_array().unwrap();
+            assert!((3..=5).contains(&items.len()));
+            for item in items {
+                match item {
+                    Value::String(tag) => assert!(matcher.is_match(tag), "{}", tag),
+                    node => assert!([0.0, 0.25, 0.5, 0.75, 1.0].contains(&node["value"].as_f64().unwrap())),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn will_merge_all_of() {
+        let schema = json!({
+            "allOf": [
+                { "type": "object", "required": ["a"], "properties": { "a": { "const": 1 } } },
+                { "required": ["b"], "properties": { "b": { "type": "boolean" } } }
+            ]
+        });
+        for document in run(schema, "5").unwrap() {
+            assert_eq!(document["a"], 1);
+            assert!(document["b"].is_boolean());
+        }
+    }
+
+    #[test]
+    fn will_respect_exclusive_bounds_of_multiples() {
+        let schema = json!({ "type": "number", "exclusiveMinimum": 0, "maximum": 1, "multipleOf": 1 });
+        for document in run(schema, "20").unwrap() {
+            assert_eq!(document.as_f64().unwrap(), 1.0);
+        }
+        let schema = json!({ "type": "number", "minimum": 0, "maximum": 1, "exclusiveMaximum": true, "multipleOf": 0.5 });
+        for document in run(schema, "20").unwrap() {
+            assert!([0.0, 0.5].contains(&document.as_f64().unwrap()));
+        }
+    }
+
+    #[test]
+    fn will_generate_number_in_single_point_range() {
+        let schema = json!({ "type": "number", "minimum": 5, "maximum": 5 });
+        assert_eq!(run(schema, "3").unwrap(), [json!(5.0), json!(5.0), json!(5.0)]);
+    }
+
+    #[rstest(schema,
+        case(json!({ "$ref": "#/definitions/missing" })),
+        case(json!({ "$ref": "other.json#/definitions/x" })),
+        case(json!({ "type": "integer", "minimum": 5, "maximum": 4 })),
+        case(json!({ "type": "integer", "minimum": 1, "maximum": 2, "multipleOf": 3 })),
+        case(json!({ "type": "number", "minimum": 5, "exclusiveMaximum": 5 })),
+        case(json!({ "type": "number", "exclusiveMinimum": 0, "exclusiveMaximum": 1, "multipleOf": 1 })),
+        case(json!({ "type": "string", "pattern": "(?=a)" })),
+        case(json!({ "type": "object", "required": ["self"], "properties": { "self": { "$ref": "#" } } })),
+        case(json!(false)),
+    )]
+    fn will_reject_unsatisfiable_schemas(schema: Value) {
+        assert!(run(schema, "1").is_err());
+    }
+
+    #[test]
+    fn will_generate_same_documents_for_same_seed() {
+        let schema = json!({
+            "type": "object",
+            "required": ["a", "b"],
+            "properties": {
+                "a": { "type": "string", "minLength": 2, "maxLength": 6 },
+                "b": { "type": "array", "items": { "enum": [1, 2, 3] } }
+            }
+        });
+        assert_eq!(run(schema, "2").unwrap(), [json!({ "a": "ax", "b": [2] }), json!({ "a": "ez", "b": [3, 1, 1, 3, 2] })]);
+    }
+
+    #[test]
+    fn will_surface_write_errors_as_io_errors() {
+        struct ClosedPipe;
+        impl std::io::Write for ClosedPipe {
+            fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+                Err(std::io::ErrorKind::BrokenPipe.into())
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let base_args = BaseArgs::try_parse_from(["medea", "fake", "json", "-s", "-"]).unwrap();
+        let error = base_args.command.run_to(&base_args, || json!({ "type": "integer" }).to_string(), &mut ClosedPipe).unwrap_err();
+        assert_eq!(error.downcast_ref::<std::io::Error>().unwrap().kind(), std::io::ErrorKind::BrokenPipe);
+    }
+}
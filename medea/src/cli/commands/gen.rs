@@ -0,0 +1,422 @@
+use std::error::Error;
+use std::fmt;
+use std::io::Write;
+
+use clap::{Parser, ValueEnum};
+use indoc::indoc;
+use serde::de::{Deserialize, Deserializer, MapAccess, Visitor};
+use serde_json::Value;
+
+use crate::cli::args::{Runnable, BaseArgs};
+use crate::cli::commands::fake::csv_row;
+use crate::cli::commands::random;
+use crate::cli::utils::size_utils;
+use crate::cli::ArgsEnum;
+
+#[derive(Parser, Debug, Clone)]
+#[command(
+    about = "Generate records from a template",
+    after_help = "See `medea help gen` for details",
+    long_about = indoc!{"
+        Render a template several times, replacing every {{...}} placeholder
+        with the output of the medea command inside it. Placeholders run in
+        the same process and share the random number generator and clock,
+        so global options like --seed and --now apply to the whole template.
+
+        Placeholders take the same arguments as the command line, plus two
+        short forms: {{uuid v7}} for {{uuid -v 7}}, and {{rnd hex 16}} for
+        {{rnd -t hex 16}}, which works with every rnd output format.
+
+        With json, csv or sql output, the template must render to json, and
+        placeholder output is escaped to fit inside a json string. For csv
+        and sql, it must render to a json object, whose keys become the
+        columns.
+    "},
+    after_long_help = indoc!{r#"
+        Examples:
+            # generate json lines fixtures
+            $ medea gen '{"id":"{{uuid v7}}","token":"{{rnd hex 16}}","name":"{{fake name}}"}' -n 2 -t json
+            {"id":"018b3d2e-7c1a-7f4e-9b1e-0c6f3a9d2e11","token":"9f2b6c0e4a1d3e5f7a8b9c0d1e2f3a4b","name":"Kyle Hernandez"}
+            {"id":"018b3d2e-7c1a-7f4e-a3c2-5d8e1f0b7a64","token":"1c3e5a7b9d0f2e4c6a8b0d2f4e6a8c0b","name":"Grace Fisher"}
+
+            # generate sql inserts
+            $ medea gen '{"email":"{{fake email}}","age":{{rnd int 18..90}}}' -n 2 -t sql --table users
+            INSERT INTO users (email, age) VALUES ('kayla.mason@example.org', 42);
+            INSERT INTO users (email, age) VALUES ('gary1967@example.net', 27);
+
+            # generate plain text
+            $ medea gen 'user {{fake username}} signed in at {{ts}}' -n 3
+    "#}
+)]
+pub struct GenArgs {
+    #[arg(help = "Template to render. If omitted, will read from stdin")]
+    template: Option<String>,
+
+    #[arg(
+        short = 'n',
+        long,
+        default_value = "1",
+        help = "Number of records to generate",
+        long_help = "Number of records to generate. Accepts suffixes like 10k, 10M or 1Gi",
+        value_parser = size_utils::parse_size
+    )]
+    count: u64,
+
+    #[arg(
+        short,
+        long,
+        help = "Output format",
+        value_enum,
+        default_value = "lines",
+        value_name = "FORMAT"
+    )]
+    to: Format,
+
+    #[arg(long, default_value = "records", help = "Table name for sql output")]
+    table: String,
+}
+
+#[derive(ValueEnum, Debug, Clone, PartialEq)]
+enum Format {
+    Lines,
+    Json,
+    Csv,
+    Sql,
+}
+
+enum Segment {
+    Text(String),
+    Command(Box<ArgsEnum>),
+}
+
+// a json object that keeps its keys in template order, so they can be used
+// as csv and sql columns
+struct OrderedObject(Vec<(String, Value)>);
+
+impl<'de> Deserialize<'de> for OrderedObject {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ObjectVisitor;
+
+        impl<'de> Visitor<'de> for ObjectVisitor {
+            type Value = OrderedObject;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a json object")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<OrderedObject, A::Error> {
+                let mut entries = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(OrderedObject(entries))
+            }
+        }
+
+        deserializer.deserialize_map(ObjectVisitor)
+    }
+}
+
+// splits a placeholder into words like a shell would, with single quotes,
+// double quotes and backslash escapes. returns the words and the rest of
+// the template after the closing braces
+fn split_placeholder(input: &str) -> Result<(Vec<String>, &str), Box<dyn Error>> {
+    let mut words: Vec<String> = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote: Option<char> = None;
+    let mut chars = input.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match (quote, c) {
+            (None, '}') if input[i..].starts_with("}}") => {
+                words.extend(word);
+                return Ok((words, &input[i + 2..]));
+            },
+            (None, c) if c.is_whitespace() => words.extend(word.take()),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            },
+            (Some(q), c) if c == q => quote = None,
+            (None | Some('"'), '\\') => match chars.next() {
+                Some((_, escaped)) => word.get_or_insert_with(String::new).push(escaped),
+                None => break,
+            },
+            (_, c) => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    Err("placeholder is missing its closing }}".into())
+}
+
+// rewrites the short forms of placeholders into regular arguments, e.g.
+// `uuid v7` into `uuid -v 7` and `rnd hex 16` into `rnd -t hex 16`
+fn expand_shorthand(mut words: Vec<String>) -> Vec<String> {
+    let (command, first) = match (words.first(), words.get(1)) {
+        (Some(command), Some(first)) => (command.as_str(), first.clone()),
+        _ => return words,
+    };
+    match command {
+        "uuid" => {
+            if let Some(version) = first.strip_prefix('v').filter(|v| !v.is_empty() && v.chars().all(|c| c.is_ascii_digit())) {
+                words.splice(1..2, [String::from("-v"), version.to_string()]);
+            }
+        },
+        "rnd" | "random" if <random::Format as ValueEnum>::from_str(&first, false).is_ok() => {
+            words.insert(1, String::from("-t"));
+        },
+        _ => {},
+    }
+    words
+}
+
+fn parse_template(template: &str) -> Result<Vec<Segment>, Box<dyn Error>> {
+    let mut segments = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            segments.push(Segment::Text(rest[..start].to_string()));
+        }
+        let (words, after) = split_placeholder(&rest[start + 2..])?;
+        let placeholder = &rest[start..rest.len() - after.len()];
+        let words = expand_shorthand(words);
+        let args = BaseArgs::try_parse_from(std::iter::once(String::from("medea")).chain(words)).map_err(|e| {
+            let error = e.render().to_string();
+            let error = error.lines().next().unwrap_or_default().trim_start_matches("error: ").to_string();
+            format!("invalid placeholder {}: {}", placeholder, error)
+        })?;
+        if let ArgsEnum::Gen(_) = args.command {
+            return Err(format!("placeholder {} can't render another template", placeholder).into());
+        }
+        segments.push(Segment::Command(Box::new(args.command)));
+        rest = after;
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Text(rest.to_string()));
+    }
+    Ok(segments)
+}
+
+// escapes the output of a placeholder so it can sit inside a json string
+fn json_escape(value: &str) -> String {
+    let quoted = Value::String(value.to_string()).to_string();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+// removes the whitespace outside of strings, keeping the keys in order
+fn minify_json(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in input.chars() {
+        match (in_string, c) {
+            (true, _) if escaped => escaped = false,
+            (true, '\\') => escaped = true,
+            (_, '"') => in_string = !in_string,
+            (false, c) if c.is_whitespace() => continue,
+            _ => {},
+        }
+        output.push(c);
+    }
+    output
+}
+
+fn sql_identifier(name: &str) -> String {
+    let plain = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    match plain {
+        true => name.to_string(),
+        false => format!("\"{}\"", name.replace('"', "\"\"")),
+    }
+}
+
+fn sql_value(value: &Value) -> String {
+    match value {
+        Value::Null => String::from("NULL"),
+        Value::Bool(b) => String::from(if *b { "TRUE" } else { "FALSE" }),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => format!("'{}'", s.replace('\'', "''")),
+        v => format!("'{}'", v.to_string().replace('\'', "''")),
+    }
+}
+
+fn csv_value(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        v => v.to_string(),
+    }
+}
+
+impl GenArgs {
+    fn render(&self, segments: &[Segment], base_args: &BaseArgs) -> Result<String, Box<dyn Error>> {
+        let mut output = String::new();
+        for segment in segments {
+            match segment {
+                Segment::Text(text) => output.push_str(text),
+                Segment::Command(command) => {
+                    let value = command.run(base_args, String::new)?;
+                    match self.to {
+                        Format::Lines => output.push_str(&value),
+                        _ => output.push_str(&json_escape(&value)),
+                    }
+                },
+            }
+        }
+        Ok(output)
+    }
+
+    fn object(rendered: &str) -> Result<OrderedObject, Box<dyn Error>> {
+        serde_json::from_str(rendered).map_err(|e| format!("template did not render to a json object: {}\n{}", e, rendered).into())
+    }
+}
+
+impl Runnable for GenArgs {
+    fn run(&self, base_args: &BaseArgs, get_input: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        let mut buffer = Vec::new();
+        self.run_to(base_args, get_input, &mut buffer)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+
+    fn run_to(&self, base_args: &BaseArgs, get_input: impl Fn() -> String, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+        let template = match &self.template {
+            Some(template) => template.clone(),
+            None => get_input().trim_end_matches(['\n', '\r']).to_string(),
+        };
+        let segments = parse_template(&template)?;
+
+        for i in 0..self.count {
+            let rendered = self.render(&segments, base_args)?;
+            let line = match self.to {
+                Format::Lines => rendered,
+                Format::Json => {
+                    serde_json::from_str::<Value>(&rendered)
+                        .map_err(|e| format!("template did not render to json: {}\n{}", e, rendered))?;
+                    minify_json(&rendered)
+                },
+                Format::Csv => {
+                    let object = GenArgs::object(&rendered)?;
+                    if i == 0 {
+                        writeln!(out, "{}", csv_row(object.0.iter().map(|(k, _)| k.as_str())))?;
+                    }
+                    let values: Vec<String> = object.0.iter().map(|(_, v)| csv_value(v)).collect();
+                    csv_row(values.iter().map(|v| v.as_str()))
+                },
+                Format::Sql => {
+                    let object = GenArgs::object(&rendered)?;
+                    let columns: Vec<String> = object.0.iter().map(|(k, _)| sql_identifier(k)).collect();
+                    let values: Vec<String> = object.0.iter().map(|(_, v)| sql_value(v)).collect();
+                    format!(
+                        "INSERT INTO {} ({}) VALUES ({});",
+                        sql_identifier(&self.table),
+                        columns.join(", "),
+                        values.join(", ")
+                    )
+                },
+            };
+            if i > 0 {
+                out.write_all(b"\n")?;
+            }
+            out.write_all(line.as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use rstest::rstest;
+
+    use crate::cli::args::{BaseArgs, Runnable};
+
+    fn run(args: &[&str]) -> Result<String, Box<dyn std::error::Error>> {
+        let base_args = BaseArgs::try_parse_from([&["medea", "--seed", "fixture", "--now", "2024-01-01T00:00:00Z", "gen"], args].concat())?;
+        base_args.command.run(&base_args, String::new)
+    }
+
+    #[rstest(input, expected_words, expected_rest,
+        case("uuid -v 7}} rest", vec!["uuid", "-v", "7"], " rest"),
+        case("rnd --regex '[a-z]{3}'}}", vec!["rnd", "--regex", "[a-z]{3}"], ""),
+        case(r#"fake "a b" c\ d ''}}"#, vec!["fake", "a b", "c d", ""], ""),
+    )]
+    fn will_split_placeholder(input: &str, expected_words: Vec<&str>, expected_rest: &str) {
+        let (words, rest) = super::split_placeholder(input).unwrap();
+        assert_eq!(words, expected_words);
+        assert_eq!(rest, expected_rest);
+    }
+
+    #[rstest(input, expected_words,
+        case(vec!["uuid", "v7"], vec!["uuid", "-v", "7"]),
+        case(vec!["uuid", "v4", "3"], vec!["uuid", "-v", "4", "3"]),
+        case(vec!["rnd", "hex", "16"], vec!["rnd", "-t", "hex", "16"]),
+        case(vec!["random", "dec-list", "4"], vec!["random", "-t", "dec-list", "4"]),
+        case(vec!["uuid", "-v", "7"], vec!["uuid", "-v", "7"]),
+        case(vec!["rnd", "int", "1..6"], vec!["rnd", "int", "1..6"]),
+        case(vec!["fake", "v7"], vec!["fake", "v7"]),
+    )]
+    fn will_expand_shorthand(input: Vec<&str>, expected_words: Vec<&str>) {
+        let words = super::expand_shorthand(input.into_iter().map(String::from).collect());
+        assert_eq!(words, expected_words);
+    }
+
+    #[test]
+    fn will_render_request_template() {
+        let result = run(&[r#"{"id":"{{uuid v7}}","token":"{{rnd hex 16}}","ts":"{{ts --to iso}}","name":"{{fake name}}"}"#, "-n", "2", "-t", "json"]).unwrap();
+        for line in result.lines() {
+            let record: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert!(record["id"].as_str().unwrap().chars().nth(14) == Some('7'));
+            assert_eq!(record["token"].as_str().unwrap().len(), 32);
+            assert_eq!(record["ts"], "2024-01-01T00:00:00+00:00");
+        }
+    }
+
+    #[test]
+    fn will_render_template() {
+        let result = run(&["at {{ts --to unix}} roll {{rnd int 1..6}}", "-n", "3"]).unwrap();
+        let matcher = regex::Regex::new(r"^at 1704067200 roll [1-6]$").unwrap();
+        assert_eq!(result.lines().count(), 3);
+        assert!(result.lines().all(|l| matcher.is_match(l)), "{}", result);
+    }
+
+    #[test]
+    fn will_generate_same_records_for_same_seed() {
+        let args = ["{{uuid -v 7}} {{fake name}}", "-n", "3"];
+        assert_eq!(run(&args).unwrap(), run(&args).unwrap());
+    }
+
+    #[test]
+    fn will_output_json_lines() {
+        let template = r#"{
+            "name": "{{rnd --regex '"[a-z]{3}"'}}",
+            "id": "{{uuid -v 4}}"
+        }"#;
+        let result = run(&[template, "-n", "2", "-t", "json"]).unwrap();
+        for line in result.lines() {
+            assert!(line.starts_with("{\"name\":\"\\\""), "{}", line);
+            let value: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(value["name"].as_str().unwrap().len(), 5);
+        }
+    }
+
+    #[test]
+    fn will_output_csv() {
+        let result = run(&[r#"{"b":"{{rnd int 1..1}}","a":"x, y","c":{{rnd int 2..2}}}"#, "-n", "2", "-t", "csv"]).unwrap();
+        assert_eq!(result, "b,a,c\n1,\"x, y\",2\n1,\"x, y\",2");
+    }
+
+    #[test]
+    fn will_output_sql() {
+        let result = run(&[r#"{"name":"O'Brien","age":{{rnd int 7..7}},"first name":null}"#, "-t", "sql", "--table", "users"]).unwrap();
+        assert_eq!(result, r#"INSERT INTO users (name, age, "first name") VALUES ('O''Brien', 7, NULL);"#);
+    }
+
+    #[rstest(args,
+        case(&["{{uuid -v 7"]),
+        case(&["{{nope}}"]),
+        case(&["{{gen x}}"]),
+        case(&["[1, 2]", "-t", "csv"]),
+        case(&["{oops", "-t", "json"]),
+    )]
+    fn will_reject_invalid_templates(args: &[&str]) {
+        assert!(run(args).is_err());
+    }
+}
@@ -1,11 +1,15 @@
 use std::error::Error;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
 
 
 use crate::cli::utils::hash_utils::DynHmacDigest;
 
 use super::super::{BaseArgs, Runnable};
 use base64ct::{Base64, Encoding};
-use clap::{Parser, ValueEnum};
+use clap::{Parser, Subcommand, ValueEnum};
+use enum_dispatch::enum_dispatch;
 use hmac::{Hmac, Mac};
 use sha1::Sha1;
 
@@ -35,11 +39,16 @@ type HmacMd5 = Hmac<Md5>;
             # generate a sha256 hash using file contents
             $ medea hash "$(cat data.txt)" --hmac "$(cat secret.txt)" -ua sha256
             147933218AAABC0B8B10A2B3A5C34684C8D94341BCF10A4736DC7270F7741851
-    "#}
+
+            # compute the git object id of a file
+            $ medea hash git-blob README.md
+    "#},
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
 )]
 pub struct HashArgs {
-    #[arg(help = "Data to be hashed")]
-    data: String,
+    #[arg(help = "Data to be hashed", required = true)]
+    data: Option<String>,
 
     #[arg(
         short,
@@ -70,6 +79,71 @@ pub struct HashArgs {
         default_value = "false"
     )]
     upper: bool,
+
+    #[command(subcommand)]
+    command: Option<HashCommand>,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+#[enum_dispatch(Runnable)]
+enum HashCommand {
+    #[command(name = "git-blob")]
+    Blob(GitBlobArgs),
+    #[command(name = "git-tree")]
+    Tree(GitTreeArgs),
+    #[command(name = "git-commit")]
+    Commit(GitCommitArgs),
+}
+
+#[derive(Parser, Debug, Clone)]
+#[command(
+    about = "Compute the git object id of a file",
+    long_about = indoc!{"
+        Compute the git object id of a file, the same way `git hash-object`
+        does. The file contents are hashed as-is, without applying any
+        line ending conversions or other filters.
+    "}
+)]
+pub struct GitBlobArgs {
+    #[arg(help = "File to hash. Use `-` to read from stdin")]
+    file: PathBuf,
+
+    #[arg(long, help = "Git object format", value_enum, default_value = "sha1")]
+    object_format: ObjectFormat,
+}
+
+#[derive(Parser, Debug, Clone)]
+#[command(
+    about = "Compute the git tree id of a directory",
+    long_about = indoc!{"
+        Compute the git tree id of a directory, as `git write-tree` would
+        if every file in it were staged. The .git directory is skipped,
+        but .gitignore is not consulted. Empty directories are left out,
+        since git does not track them.
+    "}
+)]
+pub struct GitTreeArgs {
+    #[arg(help = "Directory to hash")]
+    directory: PathBuf,
+
+    #[arg(long, help = "Git object format", value_enum, default_value = "sha1")]
+    object_format: ObjectFormat,
+}
+
+#[derive(Parser, Debug, Clone)]
+#[command(
+    about = "Compute the git object id of a raw commit",
+    long_about = indoc!{"
+        Compute the git object id of a raw commit object, such as the
+        output of `git cat-file commit HEAD`.
+    "}
+)]
+pub struct GitCommitArgs {
+    #[arg(help = "File containing the raw commit. Use `-` to read from stdin")]
+    file: PathBuf,
+
+    #[arg(long, help = "Git object format", value_enum, default_value = "sha1")]
+    object_format: ObjectFormat,
 }
 
 #[derive(ValueEnum, Debug, Clone)]
@@ -78,6 +152,12 @@ enum Format {
     B64,
 }
 
+#[derive(ValueEnum, Debug, Clone)]
+enum ObjectFormat {
+    SHA1,
+    SHA256,
+}
+
 #[derive(ValueEnum, Debug, Clone)]
 pub enum Algorithm {
     MD5,
@@ -86,31 +166,162 @@ pub enum Algorithm {
     SHA512,
 }
 
-impl Runnable for HashArgs {
+impl Algorithm {
+    pub fn hmac(&self, key: &[u8]) -> Result<Box<dyn DynHmacDigest>, Box<dyn Error>> {
+        let alg = match self {
+            Algorithm::MD5 => Box::new(HmacMd5::new_from_slice(key)?) as Box<dyn DynHmacDigest>,
+            Algorithm::SHA1 => Box::new(HmacSha1::new_from_slice(key)?),
+            Algorithm::SHA256 => Box::new(HmacSha256::new_from_slice(key)?),
+            Algorithm::SHA512 => Box::new(HmacSha512::new_from_slice(key)?),
+        };
+        Ok(alg)
+    }
+
+    pub fn digest(&self) -> Box<dyn DynDigest> {
+        match self {
+            Algorithm::MD5 => Box::new(Md5::default()),
+            Algorithm::SHA1 => Box::new(Sha1::default()),
+            Algorithm::SHA256 => Box::new(Sha256::default()),
+            Algorithm::SHA512 => Box::new(Sha512::default()),
+        }
+    }
+}
+
+impl ObjectFormat {
+    fn hash_object(&self, kind: &str, content: &[u8]) -> Vec<u8> {
+        let mut digest = match self {
+            ObjectFormat::SHA1 => Algorithm::SHA1.digest(),
+            ObjectFormat::SHA256 => Algorithm::SHA256.digest(),
+        };
+        digest.update(format!("{} {}\0", kind, content.len()).as_bytes());
+        digest.update(content);
+        digest.finalize().to_vec()
+    }
+
+    // returns None for trees without any entries, which git does not store
+    fn hash_tree(&self, directory: &Path) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+        let mut entries: Vec<(Vec<u8>, &str, Vec<u8>)> = Vec::new();
+
+        for entry in fs::read_dir(directory).map_err(|e| format!("{}: {}", directory.display(), e))? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name == ".git" {
+                continue;
+            }
+
+            let path = entry.path();
+            let metadata = fs::symlink_metadata(&path)?;
+            let file_type = metadata.file_type();
+            if file_type.is_symlink() {
+                let target = fs::read_link(&path)?.to_string_lossy().into_owned();
+                entries.push((name.into_bytes(), "120000", self.hash_object("blob", target.as_bytes())));
+            } else if file_type.is_dir() {
+                if let Some(id) = self.hash_tree(&path)? {
+                    // directories sort as though their name ended with a slash
+                    let mut sort_key = name.into_bytes();
+                    sort_key.push(b'/');
+                    entries.push((sort_key, "40000", id));
+                }
+            } else {
+                let mode = if Self::is_executable(&metadata) { "100755" } else { "100644" };
+                entries.push((name.into_bytes(), mode, self.hash_object("blob", &fs::read(&path)?)));
+            }
+        }
+
+        if entries.is_empty() {
+            return Ok(None);
+        }
+
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut content = Vec::new();
+        for (sort_key, mode, id) in entries {
+            let name = match mode {
+                "40000" => &sort_key[..sort_key.len() - 1],
+                _ => &sort_key[..],
+            };
+            content.extend_from_slice(mode.as_bytes());
+            content.push(b' ');
+            content.extend_from_slice(name);
+            content.push(0);
+            content.extend_from_slice(&id);
+        }
+
+        Ok(Some(self.hash_object("tree", &content)))
+    }
+
+    #[cfg(unix)]
+    fn is_executable(metadata: &fs::Metadata) -> bool {
+        use std::os::unix::fs::PermissionsExt;
+        // git only looks at the owner's execute bit
+        metadata.permissions().mode() & 0o100 != 0
+    }
+
+    #[cfg(not(unix))]
+    fn is_executable(_: &fs::Metadata) -> bool {
+        false
+    }
+}
+
+// stdin is read as raw bytes, since objects are often binary and the shared
+// input reader only handles text
+fn read_file_or_stdin(file: &Path, stdin: &mut dyn Read) -> Result<Vec<u8>, Box<dyn Error>> {
+    match file.to_str() {
+        Some("-") => {
+            let mut content = Vec::new();
+            stdin.read_to_end(&mut content).map_err(|e| format!("stdin: {}", e))?;
+            Ok(content)
+        }
+        _ => Ok(fs::read(file).map_err(|e| format!("{}: {}", file.display(), e))?),
+    }
+}
+
+impl Runnable for GitBlobArgs {
+    fn run(&self, _: &BaseArgs, _: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        let content = read_file_or_stdin(&self.file, &mut io::stdin().lock())?;
+        Ok(base16ct::lower::encode_string(&self.object_format.hash_object("blob", &content)))
+    }
+}
+
+impl Runnable for GitTreeArgs {
+    fn run(&self, _: &BaseArgs, _: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        if !self.directory.is_dir() {
+            return Err(format!("{}: not a directory", self.directory.display()).into());
+        }
+
+        // the top level tree is always written, even when empty
+        let id = match self.object_format.hash_tree(&self.directory)? {
+            Some(id) => id,
+            None => self.object_format.hash_object("tree", &[]),
+        };
+        Ok(base16ct::lower::encode_string(&id))
+    }
+}
+
+impl Runnable for GitCommitArgs {
     fn run(&self, _: &BaseArgs, _: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
-        let data = self.data.as_bytes();
+        let content = read_file_or_stdin(&self.file, &mut io::stdin().lock())?;
+        Ok(base16ct::lower::encode_string(&self.object_format.hash_object("commit", &content)))
+    }
+}
+
+impl Runnable for HashArgs {
+    fn run(&self, base_args: &BaseArgs, get_input: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        if let Some(command) = &self.command {
+            return command.run(base_args, get_input);
+        }
+
+        let data = self.data.as_deref().unwrap_or_default().as_bytes();
         let res: Vec<u8>;
 
         if self.hmac.is_some() {
             let key = self.hmac.clone().unwrap();
-            let mut alg = match &self.algorithm {
-                Algorithm::MD5 => {
-                    Box::new(HmacMd5::new_from_slice(key.as_bytes())?) as Box<dyn DynHmacDigest>
-                }
-                Algorithm::SHA1 => Box::new(HmacSha1::new_from_slice(key.as_bytes())?),
-                Algorithm::SHA256 => Box::new(HmacSha256::new_from_slice(key.as_bytes())?),
-                Algorithm::SHA512 => Box::new(HmacSha512::new_from_slice(key.as_bytes())?),
-            };
+            let mut alg = self.algorithm.hmac(key.as_bytes())?;
 
             alg.update(&data);
             res = alg.finalize_into_bytes();
         } else {
-            let mut alg = match &self.algorithm {
-                Algorithm::MD5 => Box::new(Md5::default()) as Box<dyn DynDigest>,
-                Algorithm::SHA1 => Box::new(Sha1::default()),
-                Algorithm::SHA512 => Box::new(Sha512::default()),
-                Algorithm::SHA256 => Box::new(Sha256::default()),
-            };
+            let mut alg = self.algorithm.digest();
 
             alg.update(data);
             res = alg.finalize().to_vec().to_owned();
@@ -135,13 +346,14 @@ mod tests {
         ArgsEnum,
     };
 
-    use super::{Algorithm, Format, HashArgs};
+    use std::{fs, path::PathBuf};
+
+    use rstest::rstest;
+
+    use super::{Algorithm, Format, GitBlobArgs, GitTreeArgs, HashArgs, HashCommand, ObjectFormat};
 
     fn base_args(a: HashArgs) -> BaseArgs {
-        BaseArgs {
-            trim: false,
-            command: ArgsEnum::Hash(a),
-        }
+        BaseArgs::fixture(ArgsEnum::Hash(a))
     }
 
     fn spoof_input(input: String) -> Box<dyn Fn() -> String> {
@@ -153,9 +365,10 @@ mod tests {
         let sut = HashArgs {
             algorithm: Algorithm::MD5,
             to: Format::B64,
-            data: String::from("foo"),
+            data: Some(String::from("foo")),
             hmac: None,
             upper: false,
+            command: None,
         };
 
         let hash = sut
@@ -169,9 +382,10 @@ mod tests {
         let sut = HashArgs {
             algorithm: Algorithm::SHA256,
             to: Format::Hex,
-            data: String::from("foo"),
+            data: Some(String::from("foo")),
             hmac: Some(String::from("bar")),
             upper: true,
+            command: None,
         };
 
         let hash = sut
@@ -182,4 +396,68 @@ mod tests {
             "147933218AAABC0B8B10A2B3A5C34684C8D94341BCF10A4736DC7270F7741851"
         );
     }
-}
+
+    #[rstest(object_format, input, expected_result,
+        case(ObjectFormat::SHA1, "", "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"),
+        case(ObjectFormat::SHA1, "hello\n", "ce013625030ba8dba906f756967f9e9ca394464a"),
+        case(ObjectFormat::SHA256, "", "473a0f4c3be8a93681a267e3b1e9a7dcda1185436fe141f7749120a303721813"),
+    )]
+    fn will_create_git_blob_hash(object_format: ObjectFormat, input: &str, expected_result: &str) {
+        let file = std::env::temp_dir().join(format!("medea-git-blob-{}-{}", std::process::id(), expected_result));
+        fs::write(&file, input).unwrap();
+        let sut = HashArgs {
+            algorithm: Algorithm::MD5,
+            to: Format::Hex,
+            data: None,
+            hmac: None,
+            upper: false,
+            command: Some(HashCommand::Blob(GitBlobArgs {
+                file: file.clone(),
+                object_format,
+            })),
+        };
+
+        let hash = sut.run(&base_args(sut.clone()), spoof_input(String::new()));
+        fs::remove_file(&file).unwrap();
+        assert_eq!(hash.unwrap(), expected_result);
+    }
+
+    #[test]
+    fn will_read_binary_stdin() {
+        let binary = [0x00, 0xff, 0xfe, 0x80, 0x0a];
+        let content = super::read_file_or_stdin(&PathBuf::from("-"), &mut &binary[..]).unwrap();
+        assert_eq!(content, binary);
+        assert_eq!(
+            base16ct::lower::encode_string(&ObjectFormat::SHA1.hash_object("blob", &content)),
+            "727fdb4c171f73ee2e1ac3bb7c0d05b6583b6e82"
+        );
+    }
+
+    #[test]
+    fn will_create_git_tree_hash() {
+        let directory = std::env::temp_dir().join(format!("medea-git-tree-{}", std::process::id()));
+        fs::create_dir_all(directory.join("a/b")).unwrap();
+        fs::create_dir_all(directory.join("a-b")).unwrap();
+        fs::create_dir_all(directory.join("empty")).unwrap();
+        fs::write(directory.join("f.txt"), "hello\n").unwrap();
+        fs::write(directory.join("a/b/c"), "x").unwrap();
+        fs::write(directory.join("a-b/z"), "y\n").unwrap();
+
+        let sut = GitTreeArgs {
+            directory: directory.clone(),
+            object_format: ObjectFormat::SHA1,
+        };
+        let base_args = BaseArgs::fixture(ArgsEnum::Hash(HashArgs {
+            algorithm: Algorithm::MD5,
+            to: Format::Hex,
+            data: None,
+            hmac: None,
+            upper: false,
+            command: Some(HashCommand::Tree(sut.clone())),
+        }));
+
+        let hash = sut.run(&base_args, spoof_input(String::new()));
+        fs::remove_dir_all(&directory).unwrap();
+        assert_eq!(hash.unwrap(), "d6e6c5f649ab9c55968edc892db5cf4bebcde7ef");
+    }
+}
\ No newline at end of file
@@ -0,0 +1,181 @@
+use std::error::Error;
+use std::io::Write;
+
+use chrono::{TimeZone, Utc};
+use clap::{Parser, Subcommand};
+use enum_dispatch::enum_dispatch;
+use indoc::indoc;
+use rand::RngCore;
+use serde_json::{json, Value};
+
+use crate::cli::{args::{Runnable, BaseArgs}, utils::{field_utils::format_fields, hex_utils, radix_utils, size_utils}};
+use crate::cli::commands::timestamp::{self, format_timestamp};
+
+use super::TimeArgs;
+
+const ALPHABET: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+const LENGTH: usize = 27;
+const BYTES: usize = 20;
+// ksuid timestamps count seconds from 2014-05-13T16:53:20Z
+const EPOCH: i64 = 1_400_000_000;
+
+#[derive(Parser, Debug, Clone)]
+#[command(
+    args_conflicts_with_subcommands = true,
+    about = "Generate and decode ksuids",
+    long_about = indoc!{"
+        Generate ksuids, a 32 bit timestamp in seconds since the ksuid
+        epoch followed by a 128 bit random payload, encoded as 27
+        characters of base62.
+    "},
+)]
+pub struct KsuidArgs {
+    #[arg(
+        default_value = "1",
+        help = "Number of ksuids to generate",
+        long_help = "Number of ksuids to generate. Accepts suffixes like 10k, 10M or 1Gi",
+        value_parser = size_utils::parse_size
+    )]
+    count: u64,
+
+    #[command(flatten)]
+    time: TimeArgs,
+
+    #[command(subcommand)]
+    command: Option<KsuidCommand>,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+#[enum_dispatch(Runnable)]
+enum KsuidCommand {
+    Decode(DecodeArgs),
+}
+
+#[derive(Parser, Debug, Clone)]
+#[command(about = "Decode the timestamp and payload of a ksuid")]
+pub struct DecodeArgs {
+    #[arg(help = "Ksuid to decode")]
+    ksuid: String,
+
+    #[arg(short = 'z', long, help = "Timezone of the embedded timestamp")]
+    timezone: Option<String>,
+
+    #[arg(long, default_value = "false", help = "Output as json")]
+    json: bool,
+}
+
+fn decode(input: &str) -> Result<[u8; BYTES], Box<dyn Error>> {
+    let input = input.trim();
+    if input.len() != LENGTH {
+        return Err(format!("expected a ksuid of {} characters, found {}", LENGTH, input.len()).into());
+    }
+
+    let bytes = radix_utils::decode_bytes(input, ALPHABET, BYTES).map_err(|e| format!("unable to parse ksuid {:?}: {}", input, e))?;
+    Ok(bytes.as_slice().try_into()?)
+}
+
+impl DecodeArgs {
+    fn decode(&self) -> Result<Vec<(&'static str, Value)>, Box<dyn Error>> {
+        let bytes = decode(&self.ksuid)?;
+        let seconds = i64::from(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])) + EPOCH;
+        let time = Utc.timestamp_opt(seconds, 0).single().ok_or("timestamp out of range")?;
+
+        Ok(vec![
+            ("ksuid", json!(radix_utils::encode_bytes(&bytes, ALPHABET, LENGTH))),
+            ("timestamp", json!(format_timestamp(&time, &timestamp::Format::Iso, &self.timezone)?)),
+            ("unix timestamp", json!(seconds)),
+            ("payload", json!(hex_utils::encode(&bytes[4..], false))),
+        ])
+    }
+}
+
+impl Runnable for DecodeArgs {
+    fn run(&self, _: &BaseArgs, _: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        format_fields(self.decode()?, self.json)
+    }
+}
+
+impl Runnable for KsuidArgs {
+    fn run(&self, base_args: &BaseArgs, get_input: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        let mut buffer = Vec::new();
+        self.run_to(base_args, get_input, &mut buffer)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+
+    fn run_to(&self, base_args: &BaseArgs, get_input: impl Fn() -> String, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+        if let Some(command) = &self.command {
+            return command.run_to(base_args, get_input, out);
+        }
+
+        let mut rng = base_args.rng();
+        let clock = self.time.clock(base_args)?;
+        for i in 0..self.count {
+            if i > 0 {
+                out.write_all(b"\n")?;
+            }
+            let time = clock.now()?;
+            let seconds = u32::try_from(time.timestamp() - EPOCH).map_err(|_| "timestamp is out of range for a ksuid")?;
+
+            let mut bytes = [0u8; BYTES];
+            bytes[..4].copy_from_slice(&seconds.to_be_bytes());
+            rng.fill_bytes(&mut bytes[4..]);
+            out.write_all(radix_utils::encode_bytes(&bytes, ALPHABET, LENGTH).as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::{
+        args::{BaseArgs, Runnable},
+        commands::id::{IdArgs, IdCommand, TimeArgs},
+        ArgsEnum,
+    };
+
+    use super::{DecodeArgs, KsuidArgs, KsuidCommand};
+
+    fn run(sut: KsuidArgs) -> Result<String, Box<dyn std::error::Error>> {
+        let base_args = BaseArgs::fixture(ArgsEnum::Id(IdArgs { command: IdCommand::Ksuid(sut.clone()) }));
+        sut.run(&base_args, String::new)
+    }
+
+    #[test]
+    fn will_decode_ksuid() {
+        let sut = KsuidArgs {
+            count: 1,
+            time: TimeArgs::default(),
+            command: Some(KsuidCommand::Decode(DecodeArgs {
+                ksuid: String::from("0ujtsYcgvSTl8PAuAdqWYSMnLOv"),
+                timezone: None,
+                json: true,
+            })),
+        };
+        let result: serde_json::Value = serde_json::from_str(&run(sut).unwrap()).unwrap();
+        assert_eq!(result["timestamp"], "2017-10-10T04:00:47+00:00");
+        assert_eq!(result["unix_timestamp"], 1507608047);
+        assert_eq!(result["payload"], "b5a1cd34b5f99d1154fb6853345c9735");
+    }
+
+    #[test]
+    fn will_generate_ksuids_with_pinned_time() {
+        let sut = KsuidArgs {
+            count: 10,
+            time: TimeArgs { time: Some(String::from("2017-10-10T04:00:47Z")) },
+            command: None,
+        };
+        let result = run(sut).unwrap();
+        assert_eq!(result.lines().count(), 10);
+        assert!(result.lines().all(|k| k.len() == 27 && k.starts_with("0ujt")));
+    }
+
+    #[test]
+    fn will_reject_time_before_epoch() {
+        let sut = KsuidArgs {
+            count: 1,
+            time: TimeArgs { time: Some(String::from("0")) },
+            command: None,
+        };
+        assert!(run(sut).is_err());
+    }
+}
@@ -0,0 +1,111 @@
+use std::error::Error;
+use std::io::Write;
+
+use chrono::{DateTime, Utc};
+use clap::{Args, Parser, Subcommand};
+use enum_dispatch::enum_dispatch;
+use indoc::indoc;
+
+use crate::cli::args::{Runnable, BaseArgs};
+use crate::cli::commands::timestamp::parse_timestamp;
+
+pub mod ulid;
+pub mod ksuid;
+pub mod snowflake;
+pub mod object_id;
+pub mod nanoid;
+
+use ulid::UlidArgs;
+use ksuid::KsuidArgs;
+use snowflake::SnowflakeArgs;
+use object_id::ObjectIdArgs;
+use nanoid::NanoidArgs;
+
+#[derive(Parser, Debug, Clone)]
+#[command(
+    about = "Generate and decode sortable identifiers",
+    after_help = "See `medea help id` for details",
+    long_about = indoc!{"
+        Generate and decode identifiers other than uuids. Like uuids, each
+        generator takes a count and joins the batch with newlines.
+    "},
+    after_long_help = indoc!{r#"
+        Examples:
+            # generate 3 ulids that sort in the order they were generated
+            $ medea id ulid 3 --monotonic
+            01HCDDH8YQ4XW6DRYB9T1JN8ZV
+            01HCDDH8YQ4XW6DRYB9T1JN8ZW
+            01HCDDH8YQ4XW6DRYB9T1JN8ZX
+
+            # decode a ksuid
+            $ medea id ksuid decode 0ujtsYcgvSTl8PAuAdqWYSMnLOv
+            ksuid:          0ujtsYcgvSTl8PAuAdqWYSMnLOv
+            timestamp:      2017-10-10T04:00:47+00:00
+            unix timestamp: 1507608047
+            payload:        b5a1cd34b5f99d1154fb6853345c9735
+
+            # find out when a discord message was sent
+            $ medea id snowflake decode 175928847299117063 --epoch discord -z Europe/London
+    "#}
+)]
+pub struct IdArgs {
+    #[command(subcommand)]
+    command: IdCommand,
+}
+
+// the embedded timestamp of the generators that have one
+#[derive(Args, Debug, Clone, Default)]
+pub struct TimeArgs {
+    #[arg(
+        long,
+        help = "Embedded timestamp",
+        long_help = indoc!{"
+            Embedded timestamp. Accepts any timestamp the timestamp command
+            can parse. If omitted, will default to the current time.
+        "}
+    )]
+    time: Option<String>,
+}
+
+impl TimeArgs {
+    // a pinned time is parsed up front rather than once per id
+    pub fn clock<'a>(&self, base_args: &'a BaseArgs) -> Result<Clock<'a>, Box<dyn Error>> {
+        let pinned = self.time.as_deref().map(parse_timestamp).transpose()?;
+        Ok(Clock { pinned, base_args })
+    }
+}
+
+// gives the time of each id in a batch
+pub struct Clock<'a> {
+    pinned: Option<DateTime<Utc>>,
+    base_args: &'a BaseArgs,
+}
+
+impl Clock<'_> {
+    pub fn now(&self) -> Result<DateTime<Utc>, Box<dyn Error>> {
+        match self.pinned {
+            Some(time) => Ok(time),
+            None => self.base_args.now(),
+        }
+    }
+}
+
+#[derive(Subcommand, Debug, Clone)]
+#[enum_dispatch(Runnable)]
+enum IdCommand {
+    Ulid(UlidArgs),
+    Ksuid(KsuidArgs),
+    Snowflake(SnowflakeArgs),
+    ObjectId(ObjectIdArgs),
+    Nanoid(NanoidArgs),
+}
+
+impl Runnable for IdArgs {
+    fn run(&self, base_args: &BaseArgs, get_input: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        self.command.run(base_args, get_input)
+    }
+
+    fn run_to(&self, base_args: &BaseArgs, get_input: impl Fn() -> String, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+        self.command.run_to(base_args, get_input, out)
+    }
+}
@@ -0,0 +1,275 @@
+use std::error::Error;
+use std::io::Write;
+
+use clap::{Args, Parser, Subcommand};
+use enum_dispatch::enum_dispatch;
+use indoc::indoc;
+use rand::RngCore;
+
+use crate::cli::{args::{Runnable, BaseArgs}, utils::size_utils};
+
+// the url-safe alphabet of the reference nanoid implementation
+const DEFAULT_ALPHABET: &str = "useandom-26T198340PX75pxJACKVERYMINDBUSHWOLF_GQZbfghjklqvwyzrict";
+
+#[derive(Parser, Debug, Clone)]
+#[command(
+    args_conflicts_with_subcommands = true,
+    about = "Generate nanoids",
+    long_about = indoc!{"
+        Generate nanoids, random ids drawn uniformly from an alphabet of up
+        to 256 characters. Random bytes are masked to the next power of two
+        and values outside of the alphabet are discarded, so every character
+        is equally likely.
+    "},
+    after_long_help = indoc!{r#"
+        Examples:
+            # generate a 10 character numeric id
+            $ medea id nanoid --size 10 --alphabet 0123456789
+            4193370526
+
+            # check how long a 12 character id lasts at 1000 ids per hour
+            $ medea id nanoid collision --size 12 --rate 1000
+            alphabet size:  64
+            entropy:        72 bits
+            ids for 1%:     9.74e9
+            time for 1%:    1112 years
+    "#}
+)]
+pub struct NanoidArgs {
+    #[arg(
+        default_value = "1",
+        help = "Number of ids to generate",
+        long_help = "Number of ids to generate. Accepts suffixes like 10k, 10M or 1Gi",
+        value_parser = size_utils::parse_size
+    )]
+    count: u64,
+
+    #[command(flatten)]
+    shape: Shape,
+
+    #[command(subcommand)]
+    command: Option<NanoidCommand>,
+}
+
+#[derive(Args, Debug, Clone)]
+struct Shape {
+    #[arg(short, long, default_value = "21", help = "Number of characters in each id")]
+    size: usize,
+
+    #[arg(
+        short,
+        long,
+        default_value = DEFAULT_ALPHABET,
+        hide_default_value = true,
+        help = "Characters to draw from [default: A-Za-z0-9_-]",
+    )]
+    alphabet: String,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+#[enum_dispatch(Runnable)]
+enum NanoidCommand {
+    Collision(CollisionArgs),
+}
+
+#[derive(Parser, Debug, Clone)]
+#[command(
+    about = "Estimate how long it takes for nanoids to collide",
+    long_about = indoc!{"
+        Estimate how many ids of the given size and alphabet can be generated,
+        and how long that takes at the given rate, before the probability of
+        at least one collision reaches the given threshold.
+    "},
+)]
+pub struct CollisionArgs {
+    #[command(flatten)]
+    shape: Shape,
+
+    #[arg(short, long, default_value = "1000", help = "Number of ids generated per hour")]
+    rate: f64,
+
+    #[arg(short, long, default_value = "0.01", help = "Acceptable probability of a collision")]
+    probability: f64,
+}
+
+impl Shape {
+    fn alphabet(&self) -> Result<Vec<char>, Box<dyn Error>> {
+        let alphabet: Vec<char> = self.alphabet.chars().collect();
+        if alphabet.is_empty() || alphabet.len() > 256 {
+            return Err("alphabet must have between 1 and 256 characters".into());
+        }
+        if let Some(c) = alphabet.iter().enumerate().find(|(i, c)| alphabet[..*i].contains(c)).map(|(_, c)| c) {
+            return Err(format!("alphabet contains {:?} more than once", c).into());
+        }
+        Ok(alphabet)
+    }
+}
+
+fn generate(alphabet: &[char], size: usize, rng: &mut impl RngCore) -> String {
+    // smallest 2^n - 1 that covers every index of the alphabet
+    let mask = (alphabet.len() as u32).next_power_of_two().max(2) - 1;
+    // enough bytes to fill the id in one pass most of the time
+    let step = ((1.6 * mask as f64 * size as f64) / alphabet.len() as f64).ceil() as usize;
+
+    let mut id = String::with_capacity(size);
+    let mut bytes = vec![0u8; step.max(1)];
+    let mut length = 0;
+    while length < size {
+        rng.fill_bytes(&mut bytes);
+        for byte in &bytes {
+            let index = (u32::from(*byte) & mask) as usize;
+            if index < alphabet.len() {
+                id.push(alphabet[index]);
+                length += 1;
+                if length == size {
+                    break;
+                }
+            }
+        }
+    }
+    id
+}
+
+fn format_duration(hours: f64) -> String {
+    let (value, unit) = match hours {
+        h if h < 1.0 / 60.0 => (h * 3600.0, "seconds"),
+        h if h < 1.0 => (h * 60.0, "minutes"),
+        h if h < 24.0 => (h, "hours"),
+        h if h < 24.0 * 365.0 => (h / 24.0, "days"),
+        h => (h / (24.0 * 365.0), "years"),
+    };
+    match value < 1e9 {
+        true => format!("{:.0} {}", value, unit),
+        false => format!("{:.2e} {}", value, unit),
+    }
+}
+
+impl CollisionArgs {
+    // birthday bound: n ~= sqrt(2 * N * ln(1 / (1 - p))) for N possible ids
+    fn ids_until_collision(&self, alphabet_size: usize) -> f64 {
+        let ln_possible_ids = self.shape.size as f64 * (alphabet_size as f64).ln();
+        let ln_threshold = (-(1.0 - self.probability).ln()).ln();
+        (0.5 * (2f64.ln() + ln_possible_ids + ln_threshold)).exp()
+    }
+}
+
+impl Runnable for CollisionArgs {
+    fn run(&self, _: &BaseArgs, _: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        let alphabet = self.shape.alphabet()?;
+        if !(self.probability > 0.0 && self.probability < 1.0) {
+            return Err("probability must be between 0 and 1".into());
+        }
+        if self.rate <= 0.0 {
+            return Err("rate must be greater than 0".into());
+        }
+
+        let bits = self.shape.size as f64 * (alphabet.len() as f64).log2();
+        let ids = self.ids_until_collision(alphabet.len());
+        let percent = format!("{:.4}", self.probability * 100.0);
+        let percent = format!("{}%", percent.trim_end_matches('0').trim_end_matches('.'));
+
+        let lines = [
+            ("alphabet size:", alphabet.len().to_string()),
+            ("entropy:", format!("{:.0} bits", bits)),
+            (&format!("ids for {}:", percent), format!("{:.2e}", ids)),
+            (&format!("time for {}:", percent), format_duration(ids / self.rate)),
+        ];
+        Ok(lines.iter().map(|(k, v)| format!("{:<16}{}", k, v)).collect::<Vec<String>>().join("\n"))
+    }
+}
+
+impl Runnable for NanoidArgs {
+    fn run(&self, base_args: &BaseArgs, get_input: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        let mut buffer = Vec::new();
+        self.run_to(base_args, get_input, &mut buffer)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+
+    fn run_to(&self, base_args: &BaseArgs, get_input: impl Fn() -> String, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+        if let Some(command) = &self.command {
+            return command.run_to(base_args, get_input, out);
+        }
+
+        let alphabet = self.shape.alphabet()?;
+        let mut rng = base_args.rng();
+        for i in 0..self.count {
+            if i > 0 {
+                out.write_all(b"\n")?;
+            }
+            out.write_all(generate(&alphabet, self.shape.size, &mut *rng).as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::mock::StepRng;
+    use rstest::rstest;
+
+    use crate::cli::{
+        args::{BaseArgs, Runnable},
+        commands::id::{IdArgs, IdCommand},
+        ArgsEnum,
+    };
+
+    use super::{CollisionArgs, NanoidArgs, NanoidCommand, Shape, DEFAULT_ALPHABET};
+
+    fn shape(size: usize, alphabet: &str) -> Shape {
+        Shape { size, alphabet: String::from(alphabet) }
+    }
+
+    fn run(sut: NanoidArgs) -> Result<String, Box<dyn std::error::Error>> {
+        let base_args = BaseArgs::fixture(ArgsEnum::Id(IdArgs { command: IdCommand::Nanoid(sut.clone()) }));
+        sut.run(&base_args, String::new)
+    }
+
+    #[test]
+    fn will_generate_batch_of_nanoids() {
+        let sut = NanoidArgs { count: 5, shape: shape(21, DEFAULT_ALPHABET), command: None };
+        let result = run(sut).unwrap();
+
+        assert_eq!(result.lines().count(), 5);
+        assert!(result.lines().all(|id| id.chars().count() == 21 && id.chars().all(|c| DEFAULT_ALPHABET.contains(c))));
+    }
+
+    #[test]
+    fn will_generate_same_nanoid_for_same_seed() {
+        let sut = NanoidArgs { count: 1, shape: shape(21, DEFAULT_ALPHABET), command: None };
+        assert_eq!(run(sut).unwrap(), "9PMPA7jgRmc9TetKMWum6");
+    }
+
+    #[test]
+    fn will_discard_bytes_outside_alphabet() {
+        // bytes 0, 1, 2, 3, ... masked with 0b11 give indices 0, 1, 2, 3, 0, ... and index 3 is skipped
+        let mut rng = StepRng::new(0x0706_0504_0302_0100, 0);
+        let alphabet: Vec<char> = "abc".chars().collect();
+        assert_eq!(super::generate(&alphabet, 6, &mut rng), "abcabc");
+    }
+
+    #[rstest(alphabet,
+        case(""),
+        case("aba"),
+    )]
+    fn will_reject_invalid_alphabet(alphabet: &str) {
+        let sut = NanoidArgs { count: 1, shape: shape(21, alphabet), command: None };
+        assert!(run(sut).is_err());
+    }
+
+    #[test]
+    fn will_estimate_collision_probability() {
+        let sut = NanoidArgs {
+            count: 1,
+            shape: shape(21, DEFAULT_ALPHABET),
+            command: Some(NanoidCommand::Collision(CollisionArgs {
+                shape: shape(12, DEFAULT_ALPHABET),
+                rate: 1000.0,
+                probability: 0.01,
+            })),
+        };
+        let result = run(sut).unwrap();
+        assert!(result.contains("entropy:        72 bits"));
+        assert!(result.contains("ids for 1%:     9.74e9"));
+        assert!(result.contains("time for 1%:    1112 years"));
+    }
+}
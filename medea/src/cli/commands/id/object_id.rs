@@ -0,0 +1,200 @@
+use std::error::Error;
+use std::io::Write;
+
+use chrono::{TimeZone, Utc};
+use clap::{Parser, Subcommand};
+use enum_dispatch::enum_dispatch;
+use indoc::indoc;
+use rand::{Rng, RngCore};
+use serde_json::{json, Value};
+
+use crate::cli::{args::{Runnable, BaseArgs}, utils::{field_utils::format_fields, hex_utils, size_utils}};
+use crate::cli::commands::timestamp::{self, format_timestamp};
+
+use super::TimeArgs;
+
+const COUNTER_MASK: u32 = 0x00FF_FFFF;
+
+#[derive(Parser, Debug, Clone)]
+#[command(
+    args_conflicts_with_subcommands = true,
+    about = "Generate and decode mongodb object ids",
+    long_about = indoc!{"
+        Generate mongodb object ids, made up of a 4 byte timestamp in
+        seconds, a 5 byte random value and a 3 byte counter. Ids in the
+        same batch share the random value and have increasing counters,
+        like ids created by a single mongodb client.
+    "},
+    after_long_help = indoc!{r#"
+        Examples:
+            $ medea id object-id decode 'ObjectId("507f1f77bcf86cd799439011")' -z America/Edmonton
+            object id:      507f1f77bcf86cd799439011
+            timestamp:      2012-10-17T15:13:27-06:00
+            unix timestamp: 1350508407
+            random:         bcf86cd799
+            counter:        4427793
+    "#}
+)]
+pub struct ObjectIdArgs {
+    #[arg(
+        default_value = "1",
+        help = "Number of object ids to generate",
+        long_help = "Number of object ids to generate. Accepts suffixes like 10k, 10M or 1Gi",
+        value_parser = size_utils::parse_size
+    )]
+    count: u64,
+
+    #[command(flatten)]
+    time: TimeArgs,
+
+    #[arg(short, long, default_value = "false")]
+    upper: bool,
+
+    #[command(subcommand)]
+    command: Option<ObjectIdCommand>,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+#[enum_dispatch(Runnable)]
+enum ObjectIdCommand {
+    Decode(DecodeArgs),
+}
+
+#[derive(Parser, Debug, Clone)]
+#[command(about = "Decode the creation time of an object id")]
+pub struct DecodeArgs {
+    #[arg(help = "Object id to decode, as hex or as an ObjectId(\"...\") literal")]
+    object_id: String,
+
+    #[arg(short = 'z', long, help = "Timezone of the embedded timestamp")]
+    timezone: Option<String>,
+
+    #[arg(long, default_value = "false", help = "Output as json")]
+    json: bool,
+}
+
+impl DecodeArgs {
+    fn decode(&self) -> Result<Vec<(&'static str, Value)>, Box<dyn Error>> {
+        let input = self.object_id.trim();
+        let hex = input
+            .strip_prefix("ObjectId(")
+            .and_then(|s| s.strip_suffix(')'))
+            .map(|s| s.trim_matches(['"', '\'']))
+            .unwrap_or(input);
+        if hex.len() != 24 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("expected an object id of 24 hex characters, found {:?}", input).into());
+        }
+
+        let bytes = hex_utils::decode(hex.to_string());
+        let seconds = i64::from(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]));
+        let time = Utc.timestamp_opt(seconds, 0).single().ok_or("timestamp out of range")?;
+        let counter = u32::from_be_bytes([0, bytes[9], bytes[10], bytes[11]]);
+
+        Ok(vec![
+            ("object id", json!(hex.to_lowercase())),
+            ("timestamp", json!(format_timestamp(&time, &timestamp::Format::Iso, &self.timezone)?)),
+            ("unix timestamp", json!(seconds)),
+            ("random", json!(hex_utils::encode(&bytes[4..9], false))),
+            ("counter", json!(counter)),
+        ])
+    }
+}
+
+impl Runnable for DecodeArgs {
+    fn run(&self, _: &BaseArgs, _: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        format_fields(self.decode()?, self.json)
+    }
+}
+
+impl Runnable for ObjectIdArgs {
+    fn run(&self, base_args: &BaseArgs, get_input: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        let mut buffer = Vec::new();
+        self.run_to(base_args, get_input, &mut buffer)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+
+    fn run_to(&self, base_args: &BaseArgs, get_input: impl Fn() -> String, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+        if let Some(command) = &self.command {
+            return command.run_to(base_args, get_input, out);
+        }
+
+        let mut rng = base_args.rng();
+        let mut random = [0u8; 5];
+        rng.fill_bytes(&mut random);
+        let mut counter = rng.gen::<u32>() & COUNTER_MASK;
+
+        let clock = self.time.clock(base_args)?;
+        for i in 0..self.count {
+            if i > 0 {
+                out.write_all(b"\n")?;
+            }
+            let time = clock.now()?;
+            let seconds = u32::try_from(time.timestamp()).map_err(|_| "timestamp is out of range for an object id")?;
+
+            let mut bytes = Vec::with_capacity(12);
+            bytes.extend_from_slice(&seconds.to_be_bytes());
+            bytes.extend_from_slice(&random);
+            bytes.extend_from_slice(&counter.to_be_bytes()[1..]);
+            out.write_all(hex_utils::encode(&bytes, self.upper).as_bytes())?;
+
+            counter = (counter + 1) & COUNTER_MASK;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::cli::{
+        args::{BaseArgs, Runnable},
+        commands::id::{IdArgs, IdCommand, TimeArgs},
+        ArgsEnum,
+    };
+
+    use super::{DecodeArgs, ObjectIdArgs, ObjectIdCommand};
+
+    fn run(sut: ObjectIdArgs) -> Result<String, Box<dyn std::error::Error>> {
+        let base_args = BaseArgs::fixture(ArgsEnum::Id(IdArgs { command: IdCommand::ObjectId(sut.clone()) }));
+        sut.run(&base_args, String::new)
+    }
+
+    #[rstest(object_id, timezone, expected_result,
+        case("507f1f77bcf86cd799439011", None, "2012-10-17T21:13:27+00:00"),
+        case("ObjectId(\"507F1F77BCF86CD799439011\")", Some("America/Edmonton"), "2012-10-17T15:13:27-06:00"),
+    )]
+    fn will_decode_object_id(object_id: &str, timezone: Option<&str>, expected_result: &str) {
+        let sut = ObjectIdArgs {
+            count: 1,
+            time: TimeArgs::default(),
+            upper: false,
+            command: Some(ObjectIdCommand::Decode(DecodeArgs {
+                object_id: String::from(object_id),
+                timezone: timezone.map(String::from),
+                json: true,
+            })),
+        };
+        let result: serde_json::Value = serde_json::from_str(&run(sut).unwrap()).unwrap();
+        assert_eq!(result["timestamp"], expected_result);
+        assert_eq!(result["unix_timestamp"], 1350508407);
+        assert_eq!(result["counter"], 0x439011);
+    }
+
+    #[test]
+    fn will_generate_object_ids_with_increasing_counter() {
+        let sut = ObjectIdArgs {
+            count: 2,
+            time: TimeArgs { time: Some(String::from("1350508407")) },
+            upper: false,
+            command: None,
+        };
+        let result = run(sut).unwrap();
+        let ids: Vec<&str> = result.lines().collect();
+
+        assert!(ids.iter().all(|id| id.len() == 24 && id.starts_with("507f1f77")));
+        assert_eq!(ids[0][8..18], ids[1][8..18]);
+        let counters: Vec<u32> = ids.iter().map(|id| u32::from_str_radix(&id[18..], 16).unwrap()).collect();
+        assert_eq!((counters[0] + 1) & 0x00FF_FFFF, counters[1]);
+    }
+}
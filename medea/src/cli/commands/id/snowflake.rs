@@ -0,0 +1,356 @@
+use std::error::Error;
+use std::io::Write;
+
+use chrono::{DateTime, TimeZone, Utc};
+use clap::{Args, Parser, Subcommand};
+use enum_dispatch::enum_dispatch;
+use indoc::indoc;
+use serde_json::{json, Value};
+
+use crate::cli::{args::{Runnable, BaseArgs}, utils::{field_utils::format_fields, size_utils}};
+use crate::cli::commands::timestamp::{self, format_timestamp};
+
+use super::TimeArgs;
+
+const DISCORD_EPOCH: i64 = 1_420_070_400_000;
+const TWITTER_EPOCH: i64 = 1_288_834_974_657;
+
+#[derive(Parser, Debug, Clone)]
+#[command(
+    args_conflicts_with_subcommands = true,
+    about = "Generate and decode snowflake ids",
+    long_about = indoc!{"
+        Generate snowflake ids, 64 bit integers made up of a millisecond
+        timestamp relative to a custom epoch, followed by datacenter, worker
+        and sequence numbers. The timestamp takes up all of the bits not
+        used by the other three fields, below the sign bit.
+    "},
+    after_long_help = indoc!{r#"
+        Layouts:
+            twitter  41 bit timestamp, 5 bit datacenter, 5 bit worker, 12 bit sequence
+            discord  41 bit timestamp, 5 bit worker, 5 bit process, 12 bit increment
+
+        The discord layout has the same shape as the default one, with the
+        worker in the datacenter bits and the process in the worker bits.
+        Discord documents a 42 bit timestamp that includes the sign bit,
+        which stays zero until 2084.
+
+        Examples:
+            # decode a discord id
+            $ medea id snowflake decode 175928847299117063 --epoch discord
+            snowflake:      175928847299117063
+            timestamp:      2016-04-30T11:18:25.796+00:00
+            unix millis:    1462015105796
+            datacenter:     1
+            worker:         0
+            sequence:       7
+
+            # generate ids for a custom layout with 10 bit worker numbers
+            $ medea id snowflake 2 --epoch 0 --datacenter-bits 0 --worker-bits 10 --worker 513
+    "#}
+)]
+pub struct SnowflakeArgs {
+    #[arg(
+        default_value = "1",
+        help = "Number of ids to generate",
+        long_help = "Number of ids to generate. Accepts suffixes like 10k, 10M or 1Gi",
+        value_parser = size_utils::parse_size
+    )]
+    count: u64,
+
+    #[command(flatten)]
+    layout: Layout,
+
+    #[arg(long, default_value = "0", help = "Datacenter number of generated ids")]
+    datacenter: u64,
+
+    #[arg(long, default_value = "0", help = "Worker number of generated ids")]
+    worker: u64,
+
+    #[command(flatten)]
+    time: TimeArgs,
+
+    #[command(subcommand)]
+    command: Option<SnowflakeCommand>,
+}
+
+#[derive(Args, Debug, Clone)]
+struct Layout {
+    #[arg(
+        short,
+        long,
+        default_value = "twitter",
+        value_parser = parse_epoch,
+        help = "Epoch of the timestamp (discord, twitter or unix milliseconds)",
+    )]
+    epoch: i64,
+
+    #[arg(long, default_value = "5", value_parser = clap::value_parser!(u32).range(0..=63), help = "Number of datacenter bits")]
+    datacenter_bits: u32,
+
+    #[arg(long, default_value = "5", value_parser = clap::value_parser!(u32).range(0..=63), help = "Number of worker bits")]
+    worker_bits: u32,
+
+    #[arg(long, default_value = "12", value_parser = clap::value_parser!(u32).range(0..=63), help = "Number of sequence bits")]
+    sequence_bits: u32,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+#[enum_dispatch(Runnable)]
+enum SnowflakeCommand {
+    Decode(DecodeArgs),
+}
+
+#[derive(Parser, Debug, Clone)]
+#[command(about = "Decode the timestamp, datacenter, worker and sequence of a snowflake id")]
+pub struct DecodeArgs {
+    #[arg(help = "Snowflake id to decode")]
+    snowflake: String,
+
+    #[command(flatten)]
+    layout: Layout,
+
+    #[arg(short = 'z', long, help = "Timezone of the embedded timestamp")]
+    timezone: Option<String>,
+
+    #[arg(long, default_value = "false", help = "Output as json")]
+    json: bool,
+}
+
+fn parse_epoch(input: &str) -> Result<i64, String> {
+    match input.to_lowercase().as_str() {
+        "discord" => Ok(DISCORD_EPOCH),
+        "twitter" => Ok(TWITTER_EPOCH),
+        _ => input.parse::<i64>().map_err(|_| format!("expected discord, twitter or a number of milliseconds, found {:?}", input)),
+    }
+}
+
+fn mask(bits: u32) -> u64 {
+    (1u64 << bits) - 1
+}
+
+impl Layout {
+    fn timestamp_bits(&self) -> Result<u32, Box<dyn Error>> {
+        63u32
+            .checked_sub(self.datacenter_bits + self.worker_bits + self.sequence_bits)
+            .ok_or_else(|| "layout uses more than 63 bits".into())
+    }
+
+    fn compose(&self, millis: i64, datacenter: u64, worker: u64, sequence: u64) -> Result<u64, Box<dyn Error>> {
+        let max_timestamp = mask(self.timestamp_bits()?);
+        let timestamp = millis
+            .checked_sub(self.epoch)
+            .and_then(|t| u64::try_from(t).ok())
+            .filter(|t| *t <= max_timestamp)
+            .ok_or("timestamp is out of range for this epoch and layout")?;
+
+        Ok((timestamp << (self.datacenter_bits + self.worker_bits + self.sequence_bits))
+            | (datacenter << (self.worker_bits + self.sequence_bits))
+            | (worker << self.sequence_bits)
+            | sequence)
+    }
+
+    fn decompose(&self, id: u64) -> Result<(i64, u64, u64, u64), Box<dyn Error>> {
+        let timestamp = id >> (self.datacenter_bits + self.worker_bits + self.sequence_bits);
+        let datacenter = (id >> (self.worker_bits + self.sequence_bits)) & mask(self.datacenter_bits);
+        let worker = (id >> self.sequence_bits) & mask(self.worker_bits);
+        let sequence = id & mask(self.sequence_bits);
+        let millis = (timestamp as i64).checked_add(self.epoch).ok_or("timestamp is out of range for this epoch")?;
+        Ok((millis, datacenter, worker, sequence))
+    }
+}
+
+#[derive(Default)]
+struct BatchState {
+    last_millis: i64,
+    sequence: u64,
+}
+
+impl BatchState {
+    // returns the millisecond and sequence for the next id, moving on to the
+    // next millisecond once the sequence for the current one is exhausted
+    fn next(&mut self, time: &DateTime<Utc>, sequence_bits: u32) -> (i64, u64) {
+        let millis = time.timestamp_millis();
+        if self.sequence > 0 && millis <= self.last_millis {
+            if self.sequence > mask(sequence_bits) {
+                self.last_millis += 1;
+                self.sequence = 0;
+            }
+        } else {
+            self.last_millis = millis;
+            self.sequence = 0;
+        }
+
+        self.sequence += 1;
+        (self.last_millis, self.sequence - 1)
+    }
+}
+
+impl DecodeArgs {
+    fn decode(&self) -> Result<Vec<(&'static str, Value)>, Box<dyn Error>> {
+        self.layout.timestamp_bits()?;
+        let id = self.snowflake.trim().parse::<u64>().map_err(|e| format!("unable to parse snowflake id: {}", e))?;
+        let (millis, datacenter, worker, sequence) = self.layout.decompose(id)?;
+        let time = Utc.timestamp_millis_opt(millis).single().ok_or("timestamp out of range")?;
+
+        Ok(vec![
+            ("snowflake", json!(id.to_string())),
+            ("timestamp", json!(format_timestamp(&time, &timestamp::Format::Iso, &self.timezone)?)),
+            ("unix millis", json!(millis)),
+            ("datacenter", json!(datacenter)),
+            ("worker", json!(worker)),
+            ("sequence", json!(sequence)),
+        ])
+    }
+}
+
+impl Runnable for DecodeArgs {
+    fn run(&self, _: &BaseArgs, _: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        format_fields(self.decode()?, self.json)
+    }
+}
+
+impl Runnable for SnowflakeArgs {
+    fn run(&self, base_args: &BaseArgs, get_input: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        let mut buffer = Vec::new();
+        self.run_to(base_args, get_input, &mut buffer)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+
+    fn run_to(&self, base_args: &BaseArgs, get_input: impl Fn() -> String, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+        if let Some(command) = &self.command {
+            return command.run_to(base_args, get_input, out);
+        }
+
+        if self.datacenter > mask(self.layout.datacenter_bits) {
+            return Err(format!("datacenter must fit in {} bits", self.layout.datacenter_bits).into());
+        }
+        if self.worker > mask(self.layout.worker_bits) {
+            return Err(format!("worker must fit in {} bits", self.layout.worker_bits).into());
+        }
+
+        let mut state = BatchState::default();
+        let clock = self.time.clock(base_args)?;
+        for i in 0..self.count {
+            if i > 0 {
+                out.write_all(b"\n")?;
+            }
+            let time = clock.now()?;
+            let (millis, sequence) = state.next(&time, self.layout.sequence_bits);
+            out.write_all(self.layout.compose(millis, self.datacenter, self.worker, sequence)?.to_string().as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::cli::{
+        args::{BaseArgs, Runnable},
+        commands::id::{IdArgs, IdCommand, TimeArgs},
+        ArgsEnum,
+    };
+
+    use super::{DecodeArgs, Layout, SnowflakeArgs, SnowflakeCommand, DISCORD_EPOCH, TWITTER_EPOCH};
+
+    fn layout(epoch: i64) -> Layout {
+        Layout {
+            epoch,
+            datacenter_bits: 5,
+            worker_bits: 5,
+            sequence_bits: 12,
+        }
+    }
+
+    fn args(epoch: i64) -> SnowflakeArgs {
+        SnowflakeArgs {
+            count: 1,
+            layout: layout(epoch),
+            datacenter: 0,
+            worker: 0,
+            time: TimeArgs::default(),
+            command: None,
+        }
+    }
+
+    fn run(sut: SnowflakeArgs) -> Result<String, Box<dyn std::error::Error>> {
+        let base_args = BaseArgs::fixture(ArgsEnum::Id(IdArgs { command: IdCommand::Snowflake(sut.clone()) }));
+        sut.run(&base_args, String::new)
+    }
+
+    #[rstest(id, epoch, timestamp, datacenter, worker, sequence,
+        case("175928847299117063", DISCORD_EPOCH, "2016-04-30T11:18:25.796+00:00", 1, 0, 7),
+        case("1212092628029698048", TWITTER_EPOCH, "2019-12-31T19:26:16.771+00:00", 10, 7, 0),
+    )]
+    fn will_decode_snowflake(id: &str, epoch: i64, timestamp: &str, datacenter: u64, worker: u64, sequence: u64) {
+        let sut = SnowflakeArgs {
+            command: Some(SnowflakeCommand::Decode(DecodeArgs {
+                snowflake: String::from(id),
+                layout: layout(epoch),
+                timezone: None,
+                json: true,
+            })),
+            ..args(epoch)
+        };
+        let result: serde_json::Value = serde_json::from_str(&run(sut).unwrap()).unwrap();
+        assert_eq!(result["timestamp"], timestamp);
+        assert_eq!(result["datacenter"], datacenter);
+        assert_eq!(result["worker"], worker);
+        assert_eq!(result["sequence"], sequence);
+    }
+
+    #[test]
+    fn will_reject_decoding_with_overflowing_epoch() {
+        let sut = SnowflakeArgs {
+            command: Some(SnowflakeCommand::Decode(DecodeArgs {
+                snowflake: String::from("9223372036854775807"),
+                layout: layout(i64::MAX),
+                timezone: None,
+                json: true,
+            })),
+            ..args(i64::MAX)
+        };
+        assert!(run(sut).is_err());
+    }
+
+    #[test]
+    fn will_reject_generating_with_overflowing_epoch() {
+        let sut = SnowflakeArgs {
+            time: TimeArgs { time: Some(String::from("0")) },
+            ..args(i64::MIN)
+        };
+        assert!(run(sut).is_err());
+    }
+
+    #[test]
+    fn will_generate_snowflakes_with_pinned_time() {
+        let sut = SnowflakeArgs {
+            count: 3,
+            datacenter: 10,
+            worker: 7,
+            time: TimeArgs { time: Some(String::from("2019-12-31T19:26:16.771Z")) },
+            ..args(TWITTER_EPOCH)
+        };
+        assert_eq!(run(sut).unwrap(), "1212092628029698048\n1212092628029698049\n1212092628029698050");
+    }
+
+    #[test]
+    fn will_move_to_next_millisecond_when_sequence_is_exhausted() {
+        let sut = SnowflakeArgs {
+            count: 3,
+            layout: Layout { sequence_bits: 1, ..layout(0) },
+            time: TimeArgs { time: Some(String::from("0")) },
+            ..args(0)
+        };
+        // 1 << 11 is one millisecond with 11 bits below the timestamp
+        assert_eq!(run(sut).unwrap(), "0\n1\n2048");
+    }
+
+    #[test]
+    fn will_reject_worker_outside_layout() {
+        let sut = SnowflakeArgs { worker: 32, ..args(TWITTER_EPOCH) };
+        assert!(run(sut).is_err());
+    }
+}
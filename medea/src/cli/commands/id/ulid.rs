@@ -0,0 +1,354 @@
+use std::error::Error;
+use std::io::Write;
+
+use chrono::{DateTime, TimeZone, Utc};
+use clap::{Parser, Subcommand};
+use enum_dispatch::enum_dispatch;
+use indoc::indoc;
+use rand::{Rng, RngCore};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::cli::{args::{Runnable, BaseArgs}, utils::{field_utils::format_fields, hex_utils, radix_utils, size_utils}};
+use crate::cli::commands::timestamp::{self, format_timestamp};
+
+use super::TimeArgs;
+use crate::cli::commands::uuid::ConvertFormat;
+
+// crockford's base32
+const ALPHABET: &str = "0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const LENGTH: usize = 26;
+const RANDOM_BITS: u32 = 80;
+const MAX_MILLIS: u64 = (1 << 48) - 1;
+
+#[derive(Parser, Debug, Clone)]
+#[command(
+    args_conflicts_with_subcommands = true,
+    about = "Generate and decode ulids",
+    long_about = indoc!{"
+        Generate ulids, a 48 bit millisecond timestamp followed by 80 random
+        bits, encoded as 26 characters of crockford's base32. A ulid has the
+        same size as a uuid, and can be converted to and from one.
+    "},
+)]
+pub struct UlidArgs {
+    #[arg(
+        default_value = "1",
+        help = "Number of ulids to generate",
+        long_help = "Number of ulids to generate. Accepts suffixes like 10k, 10M or 1Gi",
+        value_parser = size_utils::parse_size
+    )]
+    count: u64,
+
+    #[arg(
+        short,
+        long,
+        default_value = "false",
+        help = "Increment the random part for ulids within the same millisecond",
+        long_help = indoc!{"
+            Increment the random part instead of generating a new one when
+            several ulids in a batch share the same millisecond, so that the
+            batch is strictly increasing.
+        "}
+    )]
+    monotonic: bool,
+
+    #[command(flatten)]
+    time: TimeArgs,
+
+    #[arg(short, long, default_value = "false", help = "Use lower case characters")]
+    lower: bool,
+
+    #[command(subcommand)]
+    command: Option<UlidCommand>,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+#[enum_dispatch(Runnable)]
+enum UlidCommand {
+    Decode(DecodeArgs),
+    ToUuid(ToUuidArgs),
+    FromUuid(FromUuidArgs),
+}
+
+#[derive(Parser, Debug, Clone)]
+#[command(
+    about = "Decode the timestamp and randomness of a ulid",
+    after_long_help = indoc!{r#"
+        Examples:
+            $ medea id ulid decode 01ARZ3NDEKTSV4RRFFQ69G5FAV
+            ulid:           01ARZ3NDEKTSV4RRFFQ69G5FAV
+            timestamp:      2016-07-30T23:54:10.259+00:00
+            unix millis:    1469922850259
+            randomness:     d6764c61efb99302bd5b
+            uuid:           01563e3a-b5d3-d676-4c61-efb99302bd5b
+    "#}
+)]
+pub struct DecodeArgs {
+    #[arg(help = "Ulid to decode")]
+    ulid: String,
+
+    #[arg(short = 'z', long, help = "Timezone of the embedded timestamp")]
+    timezone: Option<String>,
+
+    #[arg(long, default_value = "false", help = "Output as json")]
+    json: bool,
+}
+
+#[derive(Parser, Debug, Clone)]
+#[command(about = "Convert ulids to uuids with the same bytes")]
+pub struct ToUuidArgs {
+    #[arg(help = "Ulid to convert. If omitted, ulids are read from stdin, one per line")]
+    ulid: Option<String>,
+}
+
+#[derive(Parser, Debug, Clone)]
+#[command(about = "Convert uuids to ulids with the same bytes")]
+pub struct FromUuidArgs {
+    #[arg(help = "Uuid to convert, in any format `uuid convert` detects. If omitted, uuids are read from stdin, one per line")]
+    uuid: Option<String>,
+
+    #[arg(short, long, default_value = "false", help = "Use lower case characters")]
+    lower: bool,
+}
+
+#[derive(Default)]
+struct BatchState {
+    last: Option<u128>,
+}
+
+impl BatchState {
+    fn next(&mut self, time: &DateTime<Utc>, monotonic: bool, rng: &mut dyn RngCore) -> Result<u128, Box<dyn Error>> {
+        let millis = u64::try_from(time.timestamp_millis())
+            .ok()
+            .filter(|m| *m <= MAX_MILLIS)
+            .ok_or("timestamp is out of range for a ulid")?;
+
+        let ulid = match self.last {
+            Some(last) if monotonic && (last >> RANDOM_BITS) as u64 >= millis => {
+                let next = last + 1;
+                if next >> RANDOM_BITS != last >> RANDOM_BITS {
+                    return Err("random part of monotonic ulid overflowed".into());
+                }
+                next
+            },
+            _ => {
+                let random = rng.gen::<u128>() & ((1 << RANDOM_BITS) - 1);
+                (u128::from(millis) << RANDOM_BITS) | random
+            },
+        };
+
+        self.last = Some(ulid);
+        Ok(ulid)
+    }
+}
+
+fn encode(ulid: u128, lower: bool) -> String {
+    let encoded = radix_utils::encode_u128(ulid, ALPHABET, LENGTH);
+    match lower {
+        true => encoded.to_lowercase(),
+        false => encoded,
+    }
+}
+
+fn decode(input: &str) -> Result<u128, Box<dyn Error>> {
+    let input = input.trim();
+    if input.len() != LENGTH {
+        return Err(format!("expected a ulid of {} characters, found {}", LENGTH, input.len()).into());
+    }
+
+    // crockford's base32 is case insensitive and maps confusable letters to digits
+    let normalized: String = input
+        .to_uppercase()
+        .chars()
+        .map(|c| match c {
+            'I' | 'L' => '1',
+            'O' => '0',
+            c => c,
+        })
+        .collect();
+    radix_utils::decode_u128(&normalized, ALPHABET).map_err(|e| format!("unable to parse ulid {:?}: {}", input, e).into())
+}
+
+fn read_inputs(input: &Option<String>, get_input: impl Fn() -> String) -> Vec<String> {
+    match input {
+        Some(input) => vec![input.clone()],
+        None => get_input().lines().map(|l| l.to_string()).collect(),
+    }
+}
+
+impl DecodeArgs {
+    fn decode(&self) -> Result<Vec<(&'static str, Value)>, Box<dyn Error>> {
+        let ulid = decode(&self.ulid)?;
+        let millis = (ulid >> RANDOM_BITS) as i64;
+        let time = Utc.timestamp_millis_opt(millis).single().ok_or("timestamp out of range")?;
+        let random = &ulid.to_be_bytes()[6..];
+
+        Ok(vec![
+            ("ulid", json!(encode(ulid, false))),
+            ("timestamp", json!(format_timestamp(&time, &timestamp::Format::Iso, &self.timezone)?)),
+            ("unix millis", json!(millis)),
+            ("randomness", json!(hex_utils::encode(random, false))),
+            ("uuid", json!(Uuid::from_u128(ulid).to_string())),
+        ])
+    }
+}
+
+impl Runnable for DecodeArgs {
+    fn run(&self, _: &BaseArgs, _: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        format_fields(self.decode()?, self.json)
+    }
+}
+
+impl Runnable for ToUuidArgs {
+    fn run(&self, _: &BaseArgs, get_input: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        let uuids = read_inputs(&self.ulid, get_input)
+            .iter()
+            .map(|ulid| Ok(Uuid::from_u128(decode(ulid)?).to_string()))
+            .collect::<Result<Vec<String>, Box<dyn Error>>>()?;
+        Ok(uuids.join("\n"))
+    }
+}
+
+impl Runnable for FromUuidArgs {
+    fn run(&self, _: &BaseArgs, get_input: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        let ulids = read_inputs(&self.uuid, get_input)
+            .iter()
+            .map(|uuid| Ok(encode(ConvertFormat::parse(uuid, None)?.as_u128(), self.lower)))
+            .collect::<Result<Vec<String>, Box<dyn Error>>>()?;
+        Ok(ulids.join("\n"))
+    }
+}
+
+impl Runnable for UlidArgs {
+    fn run(&self, base_args: &BaseArgs, get_input: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        let mut buffer = Vec::new();
+        self.run_to(base_args, get_input, &mut buffer)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+
+    fn run_to(&self, base_args: &BaseArgs, get_input: impl Fn() -> String, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+        if let Some(command) = &self.command {
+            return command.run_to(base_args, get_input, out);
+        }
+
+        let mut state = BatchState::default();
+        let mut rng = base_args.rng();
+        let clock = self.time.clock(base_args)?;
+        for i in 0..self.count {
+            if i > 0 {
+                out.write_all(b"\n")?;
+            }
+            let time = clock.now()?;
+            out.write_all(encode(state.next(&time, self.monotonic, &mut *rng)?, self.lower).as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use rstest::rstest;
+
+    use crate::cli::{
+        args::{BaseArgs, Runnable},
+        commands::id::{IdArgs, IdCommand, TimeArgs},
+        ArgsEnum,
+    };
+
+    use super::{DecodeArgs, FromUuidArgs, ToUuidArgs, UlidArgs, UlidCommand};
+
+    fn args() -> UlidArgs {
+        UlidArgs {
+            count: 1,
+            monotonic: false,
+            time: TimeArgs::default(),
+            lower: false,
+            command: None,
+        }
+    }
+
+    fn run(sut: UlidArgs, input: &str) -> Result<String, Box<dyn std::error::Error>> {
+        let base_args = BaseArgs::fixture(ArgsEnum::Id(IdArgs { command: IdCommand::Ulid(sut.clone()) }));
+        let input = String::from(input);
+        sut.run(&base_args, move || input.clone())
+    }
+
+    #[test]
+    fn will_generate_monotonic_ulids_with_pinned_time() {
+        let sut = UlidArgs {
+            count: 1000,
+            monotonic: true,
+            time: TimeArgs { time: Some(String::from("1469922850")) },
+            ..args()
+        };
+        let result = run(sut, "").unwrap();
+        let ulids: Vec<&str> = result.lines().collect();
+
+        assert_eq!(ulids.len(), 1000);
+        assert!(ulids.windows(2).all(|w| w[0] < w[1]));
+        assert!(ulids.iter().all(|u| u.starts_with("01ARZ3ND6G")));
+    }
+
+    #[test]
+    fn will_stream_counts_with_suffixes() {
+        let base_args = BaseArgs::try_parse_from(["medea", "id", "ulid", "2k", "--time", "1469922850"]).unwrap();
+        let mut buffer = Vec::new();
+        base_args.command.run_to(&base_args, String::new, &mut buffer).unwrap();
+        let result = String::from_utf8(buffer).unwrap();
+        assert_eq!(result.lines().count(), 2000);
+        assert!(result.lines().all(|u| u.starts_with("01ARZ3ND6G")));
+    }
+
+    #[rstest(ulid,
+        case("01ARZ3NDEKTSV4RRFFQ69G5FAV"),
+        case("01arz3ndektsv4rrffq69g5fav"),
+        case("O1ARZ3NDEKTSV4RRFFQ69G5FAV"),
+    )]
+    fn will_decode_ulid(ulid: &str) {
+        let sut = UlidArgs {
+            command: Some(UlidCommand::Decode(DecodeArgs {
+                ulid: String::from(ulid),
+                timezone: None,
+                json: true,
+            })),
+            ..args()
+        };
+        let result: serde_json::Value = serde_json::from_str(&run(sut, "").unwrap()).unwrap();
+        assert_eq!(result["timestamp"], "2016-07-30T23:54:10.259+00:00");
+        assert_eq!(result["unix_millis"], 1469922850259u64);
+        assert_eq!(result["randomness"], "d6764c61efb99302bd5b");
+    }
+
+    #[test]
+    fn will_reject_overflowing_ulid() {
+        let sut = UlidArgs {
+            command: Some(UlidCommand::ToUuid(ToUuidArgs { ulid: Some(String::from("81ARZ3NDEKTSV4RRFFQ69G5FAV")) })),
+            ..args()
+        };
+        assert!(run(sut, "").is_err());
+    }
+
+    #[test]
+    fn will_convert_ulids_to_uuids() {
+        let sut = UlidArgs {
+            command: Some(UlidCommand::ToUuid(ToUuidArgs { ulid: None })),
+            ..args()
+        };
+        let result = run(sut, "01ARZ3NDEKTSV4RRFFQ69G5FAV\n00000000000000000000000000\n").unwrap();
+        assert_eq!(result, "01563e3a-b5d3-d676-4c61-efb99302bd5b\n00000000-0000-0000-0000-000000000000");
+    }
+
+    #[test]
+    fn will_convert_uuid_to_ulid() {
+        let sut = UlidArgs {
+            command: Some(UlidCommand::FromUuid(FromUuidArgs {
+                uuid: Some(String::from("01563e3ab5d3d6764c61efb99302bd5b")),
+                lower: false,
+            })),
+            ..args()
+        };
+        assert_eq!(run(sut, "").unwrap(), "01ARZ3NDEKTSV4RRFFQ69G5FAV");
+    }
+}
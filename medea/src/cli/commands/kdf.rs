@@ -0,0 +1,355 @@
+use std::error::Error;
+
+use base64ct::{Base64, Encoding};
+use clap::{Parser, Subcommand, ValueEnum};
+use enum_dispatch::enum_dispatch;
+use indoc::indoc;
+
+use crate::cli::args::{Runnable, BaseArgs};
+use super::{hash::Algorithm, jwt::KeyFormat};
+
+// far more than any key needs, and small enough to hold in memory
+const MAX_PBKDF2_LENGTH: usize = 1 << 20;
+
+#[derive(Parser, Debug, Clone)]
+#[command(
+    about = "Derive keys using HKDF or PBKDF2",
+    after_help = "See `medea help kdf` for details",
+    long_about = indoc!{"
+        Derive keys from a secret using HKDF (RFC 5869) or PBKDF2 (RFC 8018).
+        Both derivation functions are built on the same hmac algorithms as
+        the hash command.
+    "},
+    after_long_help = indoc!{r#"
+        Examples:
+            # derive a 32 byte subkey with hkdf
+            $ medea kdf hkdf 'master secret' --salt 'some salt' --info 'service a'
+            57f9da2d835e157413560910b4bc57d4cc31f016e4089a18624e983c7c88be40
+
+            # derive a key from a password with pbkdf2
+            $ medea kdf pbkdf2 password --salt salt -i 4096 -l 20 -a sha1
+            4b007901b765489abead49d926f721d065a429c1
+    "#}
+)]
+pub struct KdfArgs {
+    #[command(subcommand)]
+    command: KdfCommand,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+#[enum_dispatch(Runnable)]
+enum KdfCommand {
+    Hkdf(HkdfArgs),
+    Pbkdf2(Pbkdf2Args),
+}
+
+#[derive(Parser, Debug, Clone)]
+#[command(
+    about = "Derive a key using HKDF extract and expand",
+    after_help = "See `medea help kdf hkdf` for details",
+    long_about = indoc!{"
+        Derive a key using HKDF (RFC 5869). The input keying material is
+        first run through the extract step with the salt, and the
+        resulting pseudorandom key is expanded with the info string
+        to the requested length.
+    "},
+)]
+pub struct HkdfArgs {
+    #[arg(help = "Input keying material")]
+    key: String,
+
+    #[arg(short, long, help = "Encoding format of input keying material", default_value = "ascii")]
+    from: KeyFormat,
+
+    #[arg(short, long, help = "Salt for the extract step", long_help = "Salt for the extract step. Defaults to a string of zeroes")]
+    salt: Option<String>,
+
+    #[arg(long, help = "Encoding format of salt", default_value = "ascii")]
+    salt_from: KeyFormat,
+
+    #[arg(short, long, help = "Context and application specific information", default_value = "")]
+    info: String,
+
+    #[arg(long, help = "Encoding format of info", default_value = "ascii")]
+    info_from: KeyFormat,
+
+    #[arg(short, long, help = "Length of the derived key in bytes", default_value = "32")]
+    length: usize,
+
+    #[arg(
+        long,
+        default_value = "false",
+        conflicts_with = "salt",
+        help = "Skip the extract step",
+        long_help = "Skip the extract step, using the input keying material as the pseudorandom key"
+    )]
+    expand_only: bool,
+
+    #[arg(short, long, help = "Hmac algorithm", value_enum, default_value = "sha256")]
+    algorithm: Algorithm,
+
+    #[arg(short, long, help = "Output format", value_enum, default_value = "hex", value_name = "FORMAT")]
+    to: Format,
+
+    #[arg(short, long, help = "Use upper case characters for hex output", default_value = "false")]
+    upper: bool,
+}
+
+#[derive(Parser, Debug, Clone)]
+#[command(
+    about = "Derive a key using PBKDF2",
+    after_help = "See `medea help kdf pbkdf2` for details",
+)]
+pub struct Pbkdf2Args {
+    #[arg(help = "Password to derive the key from")]
+    password: String,
+
+    #[arg(short, long, help = "Encoding format of password", default_value = "ascii")]
+    from: KeyFormat,
+
+    #[arg(short, long, help = "Salt", default_value = "")]
+    salt: String,
+
+    #[arg(long, help = "Encoding format of salt", default_value = "ascii")]
+    salt_from: KeyFormat,
+
+    #[arg(short, long, help = "Number of iterations", default_value = "100000")]
+    iterations: u32,
+
+    #[arg(short, long, help = "Length of the derived key in bytes", default_value = "32")]
+    length: usize,
+
+    #[arg(short, long, help = "Hmac algorithm", value_enum, default_value = "sha256")]
+    algorithm: Algorithm,
+
+    #[arg(short, long, help = "Output format", value_enum, default_value = "hex", value_name = "FORMAT")]
+    to: Format,
+
+    #[arg(short, long, help = "Use upper case characters for hex output", default_value = "false")]
+    upper: bool,
+}
+
+#[derive(ValueEnum, Debug, Clone)]
+enum Format {
+    Hex,
+    B64,
+}
+
+impl Format {
+    fn encode(&self, bytes: &[u8], upper: bool) -> String {
+        match self {
+            Format::B64 => Base64::encode_string(bytes),
+            Format::Hex => match upper {
+                true => base16ct::upper::encode_string(bytes),
+                false => base16ct::lower::encode_string(bytes),
+            },
+        }
+    }
+}
+
+fn hmac_output_size(algorithm: &Algorithm) -> Result<usize, Box<dyn Error>> {
+    Ok(algorithm.hmac(&[])?.finalize_into_bytes().len())
+}
+
+fn hkdf_extract(algorithm: &Algorithm, salt: &[u8], ikm: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+    let salt = match salt.is_empty() {
+        true => vec![0u8; hmac_output_size(algorithm)?],
+        false => salt.to_vec(),
+    };
+    let mut mac = algorithm.hmac(&salt)?;
+    mac.update(ikm);
+    Ok(mac.finalize_into_bytes())
+}
+
+fn hkdf_expand(algorithm: &Algorithm, prk: &[u8], info: &[u8], length: usize) -> Result<Vec<u8>, Box<dyn Error>> {
+    let hash_length = hmac_output_size(algorithm)?;
+    if length > 255 * hash_length {
+        return Err(format!("length cannot be greater than {} bytes for this algorithm", 255 * hash_length).into());
+    }
+
+    let keyed = algorithm.hmac(prk)?;
+    let mut okm = Vec::with_capacity(length);
+    let mut block: Vec<u8> = Vec::new();
+    let mut counter = 1u8;
+    while okm.len() < length {
+        let mut mac = keyed.box_clone();
+        mac.update(&block);
+        mac.update(info);
+        mac.update(&[counter]);
+        block = mac.finalize_into_bytes();
+        okm.extend_from_slice(&block[..block.len().min(length - okm.len())]);
+        counter = counter.wrapping_add(1);
+    }
+
+    Ok(okm)
+}
+
+fn pbkdf2(algorithm: &Algorithm, password: &[u8], salt: &[u8], iterations: u32, length: usize) -> Result<Vec<u8>, Box<dyn Error>> {
+    if iterations == 0 {
+        return Err("iterations must be greater than 0".into());
+    }
+
+    // the block index is a u32, which caps the length at 2^32 - 1 blocks
+    let max_length = (u32::MAX as usize).saturating_mul(hmac_output_size(algorithm)?).min(MAX_PBKDF2_LENGTH);
+    if length > max_length {
+        return Err(format!("length cannot be greater than {} bytes", max_length).into());
+    }
+
+    let keyed = algorithm.hmac(password)?;
+    let mut output = Vec::with_capacity(length);
+    let mut block_index = 1u32;
+    while output.len() < length {
+        let mut mac = keyed.box_clone();
+        mac.update(salt);
+        mac.update(&block_index.to_be_bytes());
+        let mut u = mac.finalize_into_bytes();
+        let mut block = u.clone();
+
+        for _ in 1..iterations {
+            let mut mac = keyed.box_clone();
+            mac.update(&u);
+            u = mac.finalize_into_bytes();
+            block.iter_mut().zip(u.iter()).for_each(|(b, x)| *b ^= x);
+        }
+
+        output.extend_from_slice(&block[..block.len().min(length - output.len())]);
+        block_index += 1;
+    }
+
+    Ok(output)
+}
+
+impl Runnable for HkdfArgs {
+    fn run(&self, _: &BaseArgs, _: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        let ikm = self.from.decode(&self.key)?;
+        let prk = match self.expand_only {
+            true => ikm,
+            false => {
+                let salt = self.salt.as_ref().map(|s| self.salt_from.decode(s)).transpose()?.unwrap_or_default();
+                hkdf_extract(&self.algorithm, &salt, &ikm)?
+            }
+        };
+
+        let info = self.info_from.decode(&self.info)?;
+        let okm = hkdf_expand(&self.algorithm, &prk, &info, self.length)?;
+        Ok(self.to.encode(&okm, self.upper))
+    }
+}
+
+impl Runnable for Pbkdf2Args {
+    fn run(&self, _: &BaseArgs, _: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        let password = self.from.decode(&self.password)?;
+        let salt = self.salt_from.decode(&self.salt)?;
+        let key = pbkdf2(&self.algorithm, &password, &salt, self.iterations, self.length)?;
+        Ok(self.to.encode(&key, self.upper))
+    }
+}
+
+impl Runnable for KdfArgs {
+    fn run(&self, base_args: &BaseArgs, get_input: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        self.command.run(base_args, get_input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::cli::{
+        args::{BaseArgs, Runnable},
+        commands::{hash::Algorithm, jwt::KeyFormat},
+        ArgsEnum,
+    };
+
+    use super::{Format, HkdfArgs, KdfArgs, KdfCommand, Pbkdf2Args};
+
+    fn run(command: KdfCommand) -> String {
+        let sut = KdfArgs { command };
+        let base_args = BaseArgs::fixture(ArgsEnum::Kdf(sut.clone()));
+        sut.run(&base_args, String::new).unwrap()
+    }
+
+    #[test]
+    fn will_derive_hkdf_key() {
+        let result = run(KdfCommand::Hkdf(HkdfArgs {
+            key: String::from("0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b"),
+            from: KeyFormat::Hex,
+            salt: Some(String::from("000102030405060708090a0b0c")),
+            salt_from: KeyFormat::Hex,
+            info: String::from("f0f1f2f3f4f5f6f7f8f9"),
+            info_from: KeyFormat::Hex,
+            length: 42,
+            expand_only: false,
+            algorithm: Algorithm::SHA256,
+            to: Format::Hex,
+            upper: false,
+        }));
+        assert_eq!(result, "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865");
+    }
+
+    #[test]
+    fn will_derive_hkdf_key_without_salt() {
+        let result = run(KdfCommand::Hkdf(HkdfArgs {
+            key: String::from("CwsLCwsLCwsLCwsLCwsLCwsLCwsLCw=="),
+            from: KeyFormat::B64,
+            salt: None,
+            salt_from: KeyFormat::Ascii,
+            info: String::new(),
+            info_from: KeyFormat::Ascii,
+            length: 42,
+            expand_only: false,
+            algorithm: Algorithm::SHA256,
+            to: Format::Hex,
+            upper: false,
+        }));
+        assert_eq!(result, "8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d9d201395faa4b61a96c8");
+    }
+
+    #[rstest(algorithm, iterations, length, expected_result,
+        case(Algorithm::SHA1, 1, 20, "0c60c80f961f0e71f3a9b524af6012062fe037a6"),
+        case(Algorithm::SHA1, 4096, 20, "4b007901b765489abead49d926f721d065a429c1"),
+        case(Algorithm::SHA256, 1, 32, "120fb6cffcf8b32c43e7225256c4f837a86548c92ccc35480805987cb70be17b"),
+        case(Algorithm::SHA256, 2, 40, "ae4d0c95af6b46d32d0adff928f06dd02a303f8ef3c251dfd6e2d85a95474c43830651afcb5c862f"),
+    )]
+    fn will_derive_pbkdf2_key(algorithm: Algorithm, iterations: u32, length: usize, expected_result: &str) {
+        let result = run(KdfCommand::Pbkdf2(Pbkdf2Args {
+            password: String::from("password"),
+            from: KeyFormat::Ascii,
+            salt: String::from("salt"),
+            salt_from: KeyFormat::Ascii,
+            iterations,
+            length,
+            algorithm,
+            to: Format::Hex,
+            upper: false,
+        }));
+        assert_eq!(result, expected_result);
+    }
+
+    #[test]
+    fn will_reject_pbkdf2_length_out_of_range() {
+        assert!(super::pbkdf2(&Algorithm::SHA256, b"password", b"salt", 1, 99999999999999).is_err());
+        assert!(super::pbkdf2(&Algorithm::SHA256, b"password", b"salt", 1, super::MAX_PBKDF2_LENGTH + 1).is_err());
+    }
+
+    #[rstest(key, case("xy!"), case("abc"), case("0g"))]
+    fn will_reject_invalid_hex_key(key: &str) {
+        let sut = KdfArgs {
+            command: KdfCommand::Hkdf(HkdfArgs {
+                key: String::from(key),
+                from: KeyFormat::Hex,
+                salt: None,
+                salt_from: KeyFormat::Hex,
+                info: String::new(),
+                info_from: KeyFormat::Hex,
+                length: 32,
+                expand_only: false,
+                algorithm: Algorithm::SHA256,
+                to: Format::Hex,
+                upper: false,
+            }),
+        };
+        let base_args = BaseArgs::fixture(ArgsEnum::Kdf(sut.clone()));
+        assert!(sut.run(&base_args, String::new).is_err());
+    }
+}
@@ -3,4 +3,11 @@ pub mod hash;
 pub mod timestamp;
 pub mod random;
 pub mod base_convert;
-pub mod jwt;
\ No newline at end of file
+pub mod jwt;
+pub mod crypt;
+pub mod kdf;
+pub mod otp;
+pub mod id;
+pub mod password;
+pub mod fake;
+pub mod gen;
\ No newline at end of file
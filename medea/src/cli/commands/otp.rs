@@ -0,0 +1,545 @@
+use std::error::Error;
+
+use chrono::{DateTime, Utc};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use enum_dispatch::enum_dispatch;
+use indoc::indoc;
+use rand::{Rng, RngCore};
+use serde_json::{json, Map, Value};
+
+use crate::cli::{args::{Runnable, BaseArgs}, utils::base32_utils};
+use super::{hash::Algorithm, timestamp::parse_timestamp};
+
+#[derive(Parser, Debug, Clone)]
+#[command(
+    about = "Generate and verify one-time passwords",
+    after_help = "See `medea help otp` for details",
+    long_about = indoc!{"
+        Generate and verify HOTP (RFC 4226) and TOTP (RFC 6238) one-time
+        passwords, and create or parse otpauth:// provisioning URIs.
+    "},
+    after_long_help = indoc!{r#"
+        Examples:
+            # get the current totp code
+            $ medea otp totp -s GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ
+            287082 (valid for 21s)
+
+            # get the totp code at a specific time
+            $ medea otp totp -s GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ --time 2009-02-13T23:31:30Z -d 8
+            89005924 (valid for 30s)
+
+            # verify a code, allowing for 2 steps of clock drift
+            $ medea otp verify 287082 -s GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ -w 2
+
+            # generate a provisioning uri with a random secret
+            $ medea otp uri --issuer Example --account alice@example.com
+            otpauth://totp/Example:alice%40example.com?secret=5GFS7PJDQ3O3GVFIRZ4JXQZ5RBY3NXCP&issuer=Example&algorithm=SHA1&digits=6&period=30
+    "#}
+)]
+pub struct OtpArgs {
+    #[command(subcommand)]
+    command: OtpCommand,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+#[enum_dispatch(Runnable)]
+enum OtpCommand {
+    Totp(TotpArgs),
+    Hotp(HotpArgs),
+    Verify(VerifyArgs),
+    Uri(UriArgs),
+}
+
+#[derive(Args, Debug, Clone)]
+struct OtpParameters {
+    #[arg(short, long, help = "Base32 encoded shared secret")]
+    secret: String,
+
+    #[arg(short, long, help = "Number of digits in the code", default_value = "6")]
+    digits: u32,
+
+    #[arg(short, long, help = "Hmac algorithm", value_enum, default_value = "sha1")]
+    algorithm: OtpAlgorithm,
+}
+
+#[derive(Parser, Debug, Clone)]
+#[command(about = "Generate a time-based one-time password")]
+pub struct TotpArgs {
+    #[command(flatten)]
+    parameters: OtpParameters,
+
+    #[arg(short, long, help = "Time step in seconds", default_value = "30")]
+    period: u64,
+
+    #[arg(
+        long,
+        help = "Time to generate the code for",
+        long_help = indoc!{"
+            Time to generate the code for. Accepts any timestamp the
+            timestamp command can parse. If omitted, will default to
+            the current time.
+        "}
+    )]
+    time: Option<String>,
+
+    #[arg(short, long, default_value = "false", help = "Only output the code")]
+    code_only: bool,
+}
+
+#[derive(Parser, Debug, Clone)]
+#[command(about = "Generate a counter-based one-time password")]
+pub struct HotpArgs {
+    #[command(flatten)]
+    parameters: OtpParameters,
+
+    #[arg(short, long, help = "Counter value")]
+    counter: u64,
+}
+
+#[derive(Parser, Debug, Clone)]
+#[command(
+    about = "Verify a one-time password",
+    long_about = indoc!{"
+        Verify a one-time password. Codes are verified as totp codes,
+        unless a counter is supplied. For totp codes, the window is the
+        number of time steps of clock drift to accept in either direction.
+        For hotp codes, it is the number of counter values to look ahead.
+    "}
+)]
+pub struct VerifyArgs {
+    #[arg(help = "Code to verify")]
+    code: String,
+
+    #[command(flatten)]
+    parameters: OtpParameters,
+
+    #[arg(short, long, help = "Time step in seconds", default_value = "30")]
+    period: u64,
+
+    #[arg(long, help = "Time to verify the code at", conflicts_with = "counter")]
+    time: Option<String>,
+
+    #[arg(short, long, help = "Counter value, to verify an hotp code")]
+    counter: Option<u64>,
+
+    #[arg(short, long, help = "Number of steps of drift to accept", default_value = "1")]
+    window: u64,
+}
+
+#[derive(Parser, Debug, Clone)]
+#[command(
+    about = "Generate or parse otpauth:// uris",
+    long_about = indoc!{"
+        Generate an otpauth:// provisioning uri, or parse an existing one
+        into its parameters. If no secret is given when generating, a
+        random 20 byte secret will be created.
+    "}
+)]
+pub struct UriArgs {
+    #[arg(help = "Uri to parse. Omit to generate a new uri")]
+    uri: Option<String>,
+
+    #[arg(short, long, help = "Base32 encoded shared secret")]
+    secret: Option<String>,
+
+    #[arg(long, help = "Account name, e.g. an email address")]
+    account: Option<String>,
+
+    #[arg(long, help = "Name of the issuing service")]
+    issuer: Option<String>,
+
+    #[arg(short = 't', long = "type", help = "Type of one-time password", value_enum, default_value = "totp")]
+    otp_type: OtpType,
+
+    #[arg(short, long, help = "Number of digits in the code", default_value = "6")]
+    digits: u32,
+
+    #[arg(short, long, help = "Hmac algorithm", value_enum, default_value = "sha1")]
+    algorithm: OtpAlgorithm,
+
+    #[arg(short, long, help = "Time step in seconds, for totp uris", default_value = "30")]
+    period: u64,
+
+    #[arg(short, long, help = "Initial counter value, for hotp uris", default_value = "0")]
+    counter: u64,
+}
+
+#[derive(ValueEnum, Debug, Clone, PartialEq)]
+enum OtpAlgorithm {
+    SHA1,
+    SHA256,
+    SHA512,
+}
+
+impl OtpAlgorithm {
+    fn hmac_algorithm(&self) -> Algorithm {
+        match self {
+            OtpAlgorithm::SHA1 => Algorithm::SHA1,
+            OtpAlgorithm::SHA256 => Algorithm::SHA256,
+            OtpAlgorithm::SHA512 => Algorithm::SHA512,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            OtpAlgorithm::SHA1 => "SHA1",
+            OtpAlgorithm::SHA256 => "SHA256",
+            OtpAlgorithm::SHA512 => "SHA512",
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, PartialEq)]
+enum OtpType {
+    Totp,
+    Hotp,
+}
+
+impl OtpParameters {
+    fn hotp(&self, counter: u64) -> Result<String, Box<dyn Error>> {
+        if !(1..=10).contains(&self.digits) {
+            return Err("digits must be between 1 and 10".into());
+        }
+
+        let key = base32_utils::decode(&self.secret)?;
+        let mut mac = self.algorithm.hmac_algorithm().hmac(&key)?;
+        mac.update(&counter.to_be_bytes());
+        let hash = mac.finalize_into_bytes();
+
+        // dynamic truncation, see RFC 4226 section 5.3
+        let offset = (hash[hash.len() - 1] & 0x0F) as usize;
+        let binary = u32::from_be_bytes([hash[offset], hash[offset + 1], hash[offset + 2], hash[offset + 3]]) & 0x7FFF_FFFF;
+        let code = u64::from(binary) % 10u64.pow(self.digits);
+
+        Ok(format!("{:0width$}", code, width = self.digits as usize))
+    }
+}
+
+fn get_time(time: &Option<String>, base_args: &BaseArgs) -> Result<DateTime<Utc>, Box<dyn Error>> {
+    match time {
+        Some(t) => parse_timestamp(t),
+        None => base_args.now(),
+    }
+}
+
+fn get_time_step(time: &DateTime<Utc>, period: u64) -> Result<(u64, u64), Box<dyn Error>> {
+    if period == 0 {
+        return Err("period must be greater than 0".into());
+    }
+    let seconds = u64::try_from(time.timestamp()).map_err(|_| "time must not be before the unix epoch")?;
+    Ok((seconds / period, period - seconds % period))
+}
+
+fn percent_encode(input: &str) -> String {
+    let mut result = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => result.push(byte as char),
+            _ => result += &format!("%{:02X}", byte),
+        }
+    }
+    result
+}
+
+fn percent_decode(input: &str) -> Result<String, Box<dyn Error>> {
+    let bytes = input.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3])?;
+                result.push(u8::from_str_radix(hex, 16).map_err(|_| format!("invalid percent encoding: %{}", hex))?);
+                i += 3;
+            },
+            b'%' => return Err("truncated percent encoding".into()),
+            b'+' => {
+                result.push(b' ');
+                i += 1;
+            },
+            b => {
+                result.push(b);
+                i += 1;
+            },
+        }
+    }
+    Ok(String::from_utf8(result)?)
+}
+
+impl Runnable for TotpArgs {
+    fn run(&self, base_args: &BaseArgs, _: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        let time = get_time(&self.time, base_args)?;
+        let (step, remaining) = get_time_step(&time, self.period)?;
+        let code = self.parameters.hotp(step)?;
+
+        match self.code_only {
+            true => Ok(code),
+            false => Ok(format!("{} (valid for {}s)", code, remaining)),
+        }
+    }
+}
+
+impl Runnable for HotpArgs {
+    fn run(&self, _: &BaseArgs, _: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        self.parameters.hotp(self.counter)
+    }
+}
+
+impl Runnable for VerifyArgs {
+    fn run(&self, base_args: &BaseArgs, _: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        let code = self.code.trim();
+        match self.counter {
+            Some(counter) => {
+                for offset in 0..=self.window {
+                    let Some(c) = counter.checked_add(offset) else { break };
+                    if self.parameters.hotp(c)? == code {
+                        return Ok(format!("code is valid (counter {})", c));
+                    }
+                }
+            },
+            None => {
+                let time = get_time(&self.time, base_args)?;
+                let (step, _) = get_time_step(&time, self.period)?;
+
+                // check the closest steps first, generating the offsets as
+                // they are needed since the window can be huge
+                let offsets = (1..=self.window)
+                    .map_while(|i| i64::try_from(i).ok())
+                    .flat_map(|i| [-i, i]);
+
+                for offset in std::iter::once(0).chain(offsets) {
+                    let Some(s) = step.checked_add_signed(offset) else { continue };
+                    if self.parameters.hotp(s)? == code {
+                        return Ok(format!("code is valid (offset {:+} steps)", offset));
+                    }
+                }
+            },
+        }
+
+        Err("code is not valid".into())
+    }
+}
+
+impl UriArgs {
+    fn generate_uri(&self, rng: &mut dyn RngCore) -> Result<String, Box<dyn Error>> {
+        let secret = match &self.secret {
+            Some(s) => {
+                // validate and normalize the secret
+                base32_utils::encode(&base32_utils::decode(s)?, false)
+            },
+            None => {
+                let bytes: Vec<u8> = (0..20).map(|_| rng.gen()).collect();
+                base32_utils::encode(&bytes, false)
+            },
+        };
+
+        let otp_type = match self.otp_type {
+            OtpType::Totp => "totp",
+            OtpType::Hotp => "hotp",
+        };
+
+        let account = self.account.clone().unwrap_or_default();
+        let label = match &self.issuer {
+            Some(issuer) => format!("{}:{}", percent_encode(issuer), percent_encode(&account)),
+            None => percent_encode(&account),
+        };
+
+        let mut uri = format!("otpauth://{}/{}?secret={}", otp_type, label, secret);
+        if let Some(issuer) = &self.issuer {
+            uri += &format!("&issuer={}", percent_encode(issuer));
+        }
+        uri += &format!("&algorithm={}&digits={}", self.algorithm.name(), self.digits);
+        uri += &match self.otp_type {
+            OtpType::Totp => format!("&period={}", self.period),
+            OtpType::Hotp => format!("&counter={}", self.counter),
+        };
+
+        Ok(uri)
+    }
+
+    fn parse_uri(uri: &str) -> Result<String, Box<dyn Error>> {
+        let rest = uri.trim().strip_prefix("otpauth://").ok_or("uri must start with otpauth://")?;
+        let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let (otp_type, label) = path.split_once('/').ok_or("uri is missing a label")?;
+        if otp_type != "totp" && otp_type != "hotp" {
+            return Err(format!("unexpected otp type: {:?}", otp_type).into());
+        }
+
+        let label = percent_decode(label)?;
+        let (label_issuer, account) = match label.split_once(':') {
+            Some((i, a)) => (Some(i.to_string()), a.trim_start().to_string()),
+            None => (None, label),
+        };
+
+        let mut result = Map::new();
+        result.insert(String::from("type"), json!(otp_type));
+        result.insert(String::from("account"), json!(account));
+        if let Some(issuer) = label_issuer {
+            result.insert(String::from("issuer"), json!(issuer));
+        }
+
+        for pair in query.split('&').filter(|p| !p.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = percent_decode(value)?;
+            let value = match key {
+                "digits" | "period" | "counter" => json!(value.parse::<u64>().map_err(|e| format!("invalid {}: {}", key, e))?),
+                _ => Value::String(value),
+            };
+            result.insert(percent_decode(key)?, value);
+        }
+
+        if !result.contains_key("secret") {
+            return Err("uri is missing a secret".into());
+        }
+
+        Ok(serde_json::to_string_pretty(&Value::Object(result))?)
+    }
+}
+
+impl Runnable for UriArgs {
+    fn run(&self, base_args: &BaseArgs, _: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        match &self.uri {
+            Some(uri) => Self::parse_uri(uri),
+            None => self.generate_uri(&mut *base_args.rng()),
+        }
+    }
+}
+
+impl Runnable for OtpArgs {
+    fn run(&self, base_args: &BaseArgs, get_input: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        self.command.run(base_args, get_input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::cli::{
+        args::{BaseArgs, Runnable},
+        utils::base32_utils,
+        ArgsEnum,
+    };
+
+    use super::{OtpAlgorithm, OtpArgs, OtpCommand, OtpParameters, OtpType, TotpArgs, UriArgs, VerifyArgs};
+
+    fn run(command: OtpCommand) -> Result<String, Box<dyn std::error::Error>> {
+        let sut = OtpArgs { command };
+        let base_args = BaseArgs::fixture(ArgsEnum::Otp(sut.clone()));
+        sut.run(&base_args, String::new)
+    }
+
+    fn parameters(secret: &str, algorithm: OtpAlgorithm, digits: u32) -> OtpParameters {
+        OtpParameters {
+            secret: base32_utils::encode(secret.as_bytes(), false),
+            digits,
+            algorithm,
+        }
+    }
+
+    #[rstest(counter, expected_result,
+        case(0, "755224"),
+        case(1, "287082"),
+        case(9, "520489"),
+    )]
+    fn will_generate_hotp_code(counter: u64, expected_result: &str) {
+        let result = parameters("12345678901234567890", OtpAlgorithm::SHA1, 6).hotp(counter).unwrap();
+        assert_eq!(result, expected_result);
+    }
+
+    #[rstest(secret, algorithm, time, expected_result,
+        case("12345678901234567890", OtpAlgorithm::SHA1, "59", "94287082"),
+        case("12345678901234567890", OtpAlgorithm::SHA1, "2009-02-13T23:31:30Z", "89005924"),
+        case("12345678901234567890123456789012", OtpAlgorithm::SHA256, "1111111109", "68084774"),
+        case("1234567890123456789012345678901234567890123456789012345678901234", OtpAlgorithm::SHA512, "20000000000", "47863826"),
+    )]
+    fn will_generate_totp_code(secret: &str, algorithm: OtpAlgorithm, time: &str, expected_result: &str) {
+        let result = run(OtpCommand::Totp(TotpArgs {
+            parameters: parameters(secret, algorithm, 8),
+            period: 30,
+            time: Some(String::from(time)),
+            code_only: true,
+        })).unwrap();
+        assert_eq!(result, expected_result);
+    }
+
+    #[rstest(time, window, is_valid,
+        case("59", 0, true),
+        case("89", 0, false),
+        case("89", 1, true),
+        case("119", 1, false),
+    )]
+    fn will_verify_totp_code_with_drift(time: &str, window: u64, is_valid: bool) {
+        let result = run(OtpCommand::Verify(VerifyArgs {
+            code: String::from("94287082"),
+            parameters: parameters("12345678901234567890", OtpAlgorithm::SHA1, 8),
+            period: 30,
+            time: Some(String::from(time)),
+            counter: None,
+            window,
+        }));
+        assert_eq!(result.is_ok(), is_valid);
+    }
+
+    #[test]
+    fn will_verify_totp_code_with_huge_window() {
+        let result = run(OtpCommand::Verify(VerifyArgs {
+            code: String::from("94287082"),
+            parameters: parameters("12345678901234567890", OtpAlgorithm::SHA1, 8),
+            period: 30,
+            time: Some(String::from("89")),
+            counter: None,
+            window: u64::MAX,
+        }));
+        assert_eq!(result.unwrap(), "code is valid (offset -1 steps)");
+    }
+
+    #[test]
+    fn will_verify_hotp_code_near_the_end_of_the_counter() {
+        let verify = |code: String| run(OtpCommand::Verify(VerifyArgs {
+            code,
+            parameters: parameters("12345678901234567890", OtpAlgorithm::SHA1, 6),
+            period: 30,
+            time: None,
+            counter: Some(u64::MAX - 1),
+            window: 5,
+        }));
+        let code = parameters("12345678901234567890", OtpAlgorithm::SHA1, 6).hotp(u64::MAX).unwrap();
+        assert_eq!(verify(code).unwrap(), format!("code is valid (counter {})", u64::MAX));
+        assert!(verify(String::from("000000")).is_err());
+    }
+
+    #[test]
+    fn will_round_trip_uri() {
+        let uri = run(OtpCommand::Uri(UriArgs {
+            uri: None,
+            secret: Some(String::from("gezdgnbvgy3tqojq")),
+            account: Some(String::from("alice@example.com")),
+            issuer: Some(String::from("Example Co")),
+            otp_type: OtpType::Totp,
+            digits: 6,
+            algorithm: OtpAlgorithm::SHA256,
+            period: 30,
+            counter: 0,
+        })).unwrap();
+        assert_eq!(uri, "otpauth://totp/Example%20Co:alice%40example.com?secret=GEZDGNBVGY3TQOJQ&issuer=Example%20Co&algorithm=SHA256&digits=6&period=30");
+
+        let parsed = run(OtpCommand::Uri(UriArgs {
+            uri: Some(uri),
+            secret: None,
+            account: None,
+            issuer: None,
+            otp_type: OtpType::Totp,
+            digits: 6,
+            algorithm: OtpAlgorithm::SHA1,
+            period: 30,
+            counter: 0,
+        })).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&parsed).unwrap();
+        assert_eq!(parsed["account"], "alice@example.com");
+        assert_eq!(parsed["issuer"], "Example Co");
+        assert_eq!(parsed["secret"], "GEZDGNBVGY3TQOJQ");
+        assert_eq!(parsed["algorithm"], "SHA256");
+        assert_eq!(parsed["period"], 30);
+    }
+}
@@ -0,0 +1,239 @@
+use std::error::Error;
+use std::fmt::Debug;
+use std::io::IsTerminal;
+
+use clap::Parser;
+use indoc::indoc;
+use serde_json::{json, Value};
+use zxcvbn::{matching::patterns::MatchPattern, zxcvbn, Match};
+
+use crate::cli::args::{Runnable, BaseArgs};
+use crate::cli::utils::field_utils::format_fields;
+
+#[derive(Parser, Debug, Clone)]
+#[command(
+    about = "Estimate the strength of a password",
+    long_about = indoc!{"
+        Estimate the strength of a password with zxcvbn. The password is
+        broken down into dictionary words, keyboard patterns, repeats,
+        sequences, l33t substitutions and dates, and the number of guesses
+        needed to find it is estimated from the cheapest combination of
+        those patterns. Everything runs offline, against frequency lists
+        embedded in medea.
+    "},
+    after_long_help = indoc!{r#"
+        Scores:
+            0  too guessable, fewer than 10^3 guesses
+            1  very guessable, fewer than 10^6 guesses
+            2  somewhat guessable, fewer than 10^8 guesses
+            3  safely unguessable, fewer than 10^10 guesses
+            4  very unguessable
+
+        Examples:
+            $ medea password check 'P@ssw0rd2024'
+            score:          1 (weak)
+            guesses:        15000
+            entropy bits:   13.9
+            online 100/h:   6 days
+            online 10/s:    25 minutes
+            offline 1e4/s:  1 second
+            offline 1e10/s: less than a second
+            warning:        This is similar to a commonly used password.
+            suggestions:    Add another word or two. Uncommon words are better.
+                            Capitalization doesn't help very much.
+                            Predictable substitutions like '@' instead of 'a' don't help very much.
+            patterns:       "P@ssw0rd" is "password", number 2 in the common passwords (l33t 0 -> o, @ -> a)
+                            "2024" is a recent year
+
+            # check a password read from a file, penalizing the user's name
+            $ medea password check -i haondt < password.txt
+    "#}
+)]
+pub struct CheckArgs {
+    #[arg(
+        help = "Password to check",
+        long_help = indoc!{"
+            Password to check. If omitted, the password is read from a prompt
+            that does not echo it, or from stdin when stdin is not a terminal.
+        "}
+    )]
+    password: Option<String>,
+
+    #[arg(
+        short = 'i',
+        long = "user-input",
+        value_name = "WORD",
+        help = "Words the password should not be based on, like the user's name or email. Can be repeated",
+    )]
+    user_inputs: Vec<String>,
+
+    #[arg(long, default_value = "false", help = "Output as json")]
+    json: bool,
+}
+
+fn score_name(score: u8) -> &'static str {
+    match score {
+        0 => "very weak",
+        1 => "weak",
+        2 => "fair",
+        3 => "strong",
+        _ => "very strong",
+    }
+}
+
+// zxcvbn keeps its DictionaryType in a private module, so the variants can
+// only be told apart by their names. a dictionary added by a later version is
+// named as is rather than passed off as one of these
+fn dictionary_list(dictionary: &impl Debug) -> String {
+    let list = match format!("{:?}", dictionary).as_str() {
+        "Passwords" => "common passwords",
+        "English" => "english words",
+        "FemaleNames" => "female names",
+        "MaleNames" => "male names",
+        "Surnames" => "surnames",
+        "UsTvAndFilm" => "tv and film words",
+        "UserInputs" => "user inputs",
+        other => return format!("{} dictionary", other),
+    };
+    String::from(list)
+}
+
+fn describe_match(m: &Match) -> String {
+    let description = match &m.pattern {
+        MatchPattern::Dictionary(d) => {
+            let mut details: Vec<String> = Vec::new();
+            if d.reversed {
+                details.push(String::from("reversed"));
+            }
+            if let Some(sub) = &d.sub {
+                let mut sub: Vec<String> = sub.iter().map(|(from, to)| format!("{} -> {}", from, to)).collect();
+                sub.sort();
+                details.push(format!("l33t {}", sub.join(", ")));
+            }
+            let list = dictionary_list(&d.dictionary_name);
+            let description = match d.matched_word == m.token.to_lowercase() {
+                true => format!("is number {} in the {}", d.rank, list),
+                false => format!("is {:?}, number {} in the {}", d.matched_word, d.rank, list),
+            };
+            match details.is_empty() {
+                true => description,
+                false => format!("{} ({})", description, details.join(", ")),
+            }
+        },
+        MatchPattern::Spatial(s) => {
+            format!("is a keyboard pattern on {} with {} turn{}", s.graph, s.turns, if s.turns == 1 { "" } else { "s" })
+        },
+        MatchPattern::Repeat(r) => format!("is {:?} repeated {} times", r.base_token, r.repeat_count),
+        MatchPattern::Sequence(s) => format!("is a sequence of {} characters", s.sequence_name),
+        MatchPattern::Regex(r) => format!("is a {}", r.regex_name.replace('_', " ")),
+        MatchPattern::Date(d) => format!("is the date {:04}-{:02}-{:02}", d.year, d.month, d.day),
+        MatchPattern::BruteForce => String::from("has no pattern"),
+    };
+    format!("{:?} {}", m.token, description)
+}
+
+impl CheckArgs {
+    fn read_password(&self, get_input: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        if let Some(password) = &self.password {
+            return Ok(password.clone());
+        }
+        if std::io::stdin().is_terminal() {
+            return Ok(rpassword::prompt_password("Password: ")?);
+        }
+        let input = get_input();
+        Ok(input.strip_suffix('\n').map(|i| i.trim_end_matches('\r')).unwrap_or(&input).to_string())
+    }
+
+    fn check(&self, password: &str) -> Result<Vec<(&'static str, Value)>, Box<dyn Error>> {
+        if password.is_empty() {
+            return Err("password is empty".into());
+        }
+
+        let user_inputs: Vec<&str> = self.user_inputs.iter().map(|i| i.as_str()).collect();
+        let entropy = zxcvbn(password, &user_inputs);
+        let score = u8::from(entropy.score());
+        let crack_times = entropy.crack_times();
+
+        let mut fields = vec![
+            ("score", json!(score)),
+            ("score name", json!(score_name(score))),
+            ("guesses", json!(entropy.guesses())),
+            ("entropy bits", json!((entropy.guesses_log10() * 10f64.log2() * 10.0).round() / 10.0)),
+            ("online 100/h", json!(crack_times.online_throttling_100_per_hour().to_string())),
+            ("online 10/s", json!(crack_times.online_no_throttling_10_per_second().to_string())),
+            ("offline 1e4/s", json!(crack_times.offline_slow_hashing_1e4_per_second().to_string())),
+            ("offline 1e10/s", json!(crack_times.offline_fast_hashing_1e10_per_second().to_string())),
+        ];
+        if let Some(feedback) = entropy.feedback() {
+            if let Some(warning) = feedback.warning() {
+                fields.push(("warning", json!(warning.to_string())));
+            }
+            let suggestions: Vec<String> = feedback.suggestions().iter().map(|s| s.to_string()).collect();
+            fields.push(("suggestions", json!(suggestions)));
+        }
+        let patterns: Vec<String> = entropy.sequence().iter().map(describe_match).collect();
+        fields.push(("patterns", json!(patterns)));
+        Ok(fields)
+    }
+}
+
+impl Runnable for CheckArgs {
+    fn run(&self, _: &BaseArgs, get_input: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        let password = self.read_password(get_input)?;
+        format_fields(self.check(&password)?, self.json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use rstest::rstest;
+
+    use crate::cli::{
+        args::{BaseArgs, Runnable},
+        commands::password::PasswordArgs,
+        ArgsEnum,
+    };
+
+    use super::CheckArgs;
+
+    fn run(password: &str, user_inputs: &[&str]) -> Result<String, Box<dyn std::error::Error>> {
+        let sut = CheckArgs {
+            password: Some(String::from(password)),
+            user_inputs: user_inputs.iter().map(|i| i.to_string()).collect(),
+            json: true,
+        };
+        let base_args = BaseArgs::fixture(ArgsEnum::Password(PasswordArgs::try_parse_from(["password"]).unwrap()));
+        sut.run(&base_args, String::new)
+    }
+
+    #[rstest(password, expected_score, expected_pattern,
+        case("P@ssw0rd2024", 1, "\"P@ssw0rd\" is \"password\", number 2 in the common passwords (l33t 0 -> o, @ -> a)"),
+        case("qwertyuiop", 0, "\"qwertyuiop\" is number 22 in the common passwords"),
+        case("zxcvbnm,./", 1, "\"zxcvbnm,./\" is a keyboard pattern on qwerty with 1 turn"),
+        case("abababab", 0, "\"abababab\" is \"ab\" repeated 4 times"),
+        case("13.05.1991", 1, "\"13.05.1991\" is the date 1991-05-13"),
+        case("correcthorsebatterystaple", 4, "\"horse\" is number 701 in the common passwords"),
+        case("wilhelmina", 1, "\"wilhelmina\" is number 1009 in the female names"),
+        case("xkensington", 1, "\"kensington\" is number 6490 in the english words"),
+        case("baldwinx", 1, "\"baldwin\" is number 307 in the surnames"),
+    )]
+    fn will_find_patterns(password: &str, expected_score: u8, expected_pattern: &str) {
+        let result: serde_json::Value = serde_json::from_str(&run(password, &[]).unwrap()).unwrap();
+        assert_eq!(result["score"], expected_score);
+        assert!(result["patterns"].as_array().unwrap().contains(&serde_json::json!(expected_pattern)), "{}", result);
+    }
+
+    #[test]
+    fn will_penalize_user_inputs() {
+        let without: serde_json::Value = serde_json::from_str(&run("haondt1987!", &[]).unwrap()).unwrap();
+        let with: serde_json::Value = serde_json::from_str(&run("haondt1987!", &["haondt"]).unwrap()).unwrap();
+        assert!(with["guesses"].as_u64() < without["guesses"].as_u64());
+        assert_eq!(with["patterns"][0], "\"haondt\" is number 1 in the user inputs");
+    }
+
+    #[test]
+    fn will_reject_empty_password() {
+        assert!(run("", &[]).is_err());
+    }
+}
@@ -0,0 +1,368 @@
+use std::error::Error;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use enum_dispatch::enum_dispatch;
+use indoc::indoc;
+use rand::{Rng, RngCore};
+use serde_json::json;
+
+use crate::cli::args::{Runnable, BaseArgs};
+
+pub mod check;
+
+use check::CheckArgs;
+
+// the eff large wordlist, https://www.eff.org/dice, licensed under cc by 3.0 us
+const WORDLIST: &str = include_str!("eff_large_wordlist.txt");
+
+const UPPER: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const LOWER: &str = "abcdefghijklmnopqrstuvwxyz";
+const DIGITS: &str = "0123456789";
+const SYMBOLS: &str = "!\"#$%&'()*+,-./:;<=>?@[\\]^_`{|}~";
+const AMBIGUOUS: &str = "0Oo1lI|";
+
+#[derive(Parser, Debug, Clone)]
+#[command(
+    args_conflicts_with_subcommands = true,
+    about = "Generate and check passwords and passphrases",
+    after_help = "See `medea help password` for details",
+    long_about = indoc!{"
+        Generate passwords from a set of character classes, or passphrases
+        from the eff large wordlist. Every password contains at least one
+        character of each class. Each result is followed by its entropy,
+        the number of bits an attacker who knows the settings used to
+        generate it would have to guess.
+    "},
+    after_long_help = indoc!{r#"
+        Examples:
+            # generate a 20 character password without symbols
+            $ medea password -l 20 -c upper,lower,digits
+            Tc0Xq8wVzJm2YbQ4sNfL	119.0 bits
+
+            # generate 2 passwords that are easy to read out loud
+            $ medea password 2 --exclude-ambiguous --symbols '!@#$%'
+            B8#ukZf5@dYrs!aW	94.3 bits
+            x4Q$aT!7nHzmJ2pe	94.3 bits
+
+            # generate a passphrase of 5 capitalized words
+            $ medea password --passphrase -w 5 --capitalize first
+            Unpaved-Scorecard-Slam-Ambiance-Vaporizer	64.6 bits
+
+            # check the strength of a password without echoing it
+            $ medea password check
+    "#}
+)]
+pub struct PasswordArgs {
+    #[arg(default_value = "1", help = "Number of passwords to generate")]
+    count: u32,
+
+    #[arg(short, long, default_value = "16", conflicts_with = "passphrase", help = "Number of characters in each password")]
+    length: usize,
+
+    #[arg(
+        short,
+        long,
+        value_enum,
+        value_delimiter = ',',
+        default_value = "upper,lower,digits,symbols",
+        conflicts_with = "passphrase",
+        help = "Character classes to draw from",
+        long_help = indoc!{"
+            Comma separated character classes to draw from. Every password
+            contains at least one character of each class.
+        "}
+    )]
+    classes: Vec<CharacterClass>,
+
+    #[arg(
+        long,
+        default_value = SYMBOLS,
+        hide_default_value = true,
+        conflicts_with = "passphrase",
+        help = "Characters of the symbols class [default: all printable ascii symbols]",
+    )]
+    symbols: String,
+
+    #[arg(
+        short,
+        long,
+        default_value = "false",
+        conflicts_with = "passphrase",
+        help = "Exclude characters that are easily confused, like 0, O, 1 and l",
+    )]
+    exclude_ambiguous: bool,
+
+    #[arg(short, long, default_value = "false", help = "Generate passphrases from the eff large wordlist")]
+    passphrase: bool,
+
+    #[arg(short, long, default_value = "6", requires = "passphrase", help = "Number of words in each passphrase")]
+    words: usize,
+
+    #[arg(short, long, default_value = "-", requires = "passphrase", help = "Separator between words")]
+    separator: String,
+
+    #[arg(long, value_enum, default_value = "none", requires = "passphrase", help = "Capitalization of each word")]
+    capitalize: Capitalization,
+
+    #[arg(short, long, default_value = "false", conflicts_with = "json", help = "Only output the passwords, without their entropy")]
+    quiet: bool,
+
+    #[arg(long, default_value = "false", help = "Output as json")]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Option<PasswordCommand>,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+#[enum_dispatch(Runnable)]
+enum PasswordCommand {
+    Check(CheckArgs),
+}
+
+#[derive(ValueEnum, Debug, Clone, PartialEq)]
+enum CharacterClass {
+    Upper,
+    Lower,
+    Digits,
+    Symbols,
+}
+
+#[derive(ValueEnum, Debug, Clone, PartialEq)]
+enum Capitalization {
+    None,
+    First,
+    Upper,
+    Random,
+}
+
+impl PasswordArgs {
+    // the characters of each requested class, without duplicates and
+    // without any excluded characters
+    fn character_classes(&self) -> Result<Vec<Vec<char>>, Box<dyn Error>> {
+        if let Some(c) = self.symbols.chars().find(|c| c.is_alphanumeric() || c.is_whitespace()) {
+            return Err(format!("symbols may not contain letters, digits or whitespace, found {:?}", c).into());
+        }
+
+        let mut classes: Vec<Vec<char>> = Vec::new();
+        for class in &self.classes {
+            let characters = match class {
+                CharacterClass::Upper => UPPER,
+                CharacterClass::Lower => LOWER,
+                CharacterClass::Digits => DIGITS,
+                CharacterClass::Symbols => self.symbols.as_str(),
+            };
+            let mut characters: Vec<char> = characters
+                .chars()
+                .filter(|c| !(self.exclude_ambiguous && AMBIGUOUS.contains(*c)))
+                .collect();
+            characters.sort_unstable();
+            characters.dedup();
+
+            if characters.is_empty() {
+                return Err(format!("no characters left in the {:?} class", class).to_lowercase().into());
+            }
+            if !classes.contains(&characters) {
+                classes.push(characters);
+            }
+        }
+
+        if classes.is_empty() {
+            return Err("at least one character class is required".into());
+        }
+        if self.length < classes.len() {
+            return Err(format!("length must be at least {} to fit every character class", classes.len()).into());
+        }
+        Ok(classes)
+    }
+
+    fn generate_password(&self, classes: &[Vec<char>], rng: &mut dyn RngCore) -> String {
+        let alphabet: Vec<char> = classes.concat();
+        // draw uniformly and retry until every class is present, which keeps
+        // every valid password equally likely
+        loop {
+            let password: Vec<char> = (0..self.length).map(|_| alphabet[rng.gen_range(0..alphabet.len() as u32) as usize]).collect();
+            if classes.iter().all(|class| password.iter().any(|c| class.contains(c))) {
+                return password.into_iter().collect();
+            }
+        }
+    }
+
+    fn generate_passphrase(&self, words: &[&str], rng: &mut dyn RngCore) -> String {
+        (0..self.words)
+            .map(|_| {
+                let word = words[rng.gen_range(0..words.len() as u32) as usize];
+                let capitalization = match &self.capitalize {
+                    Capitalization::Random if rng.gen::<bool>() => &Capitalization::First,
+                    Capitalization::Random => &Capitalization::None,
+                    c => c,
+                };
+                match capitalization {
+                    Capitalization::First => word[..1].to_uppercase() + &word[1..],
+                    Capitalization::Upper => word.to_uppercase(),
+                    _ => word.to_string(),
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(&self.separator)
+    }
+}
+
+fn wordlist() -> Vec<&'static str> {
+    WORDLIST.lines().filter_map(|line| line.split('\t').nth(1)).collect()
+}
+
+// log2 of the number of passwords of the given length that contain at least
+// one character of every class, counted with inclusion-exclusion over the
+// classes that are missing
+fn password_entropy(classes: &[Vec<char>], length: usize) -> f64 {
+    let total = classes.iter().map(|c| c.len()).sum::<usize>() as f64;
+    let mut fraction = 0.0;
+    for missing in 0..(1u32 << classes.len()) {
+        let missing_size: usize = classes
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| missing & (1 << i) != 0)
+            .map(|(_, c)| c.len())
+            .sum();
+        let sign = if missing.count_ones() % 2 == 0 { 1.0 } else { -1.0 };
+        fraction += sign * ((total - missing_size as f64) / total).powi(length as i32);
+    }
+    length as f64 * total.log2() + fraction.log2()
+}
+
+fn passphrase_entropy(word_count: usize, words: usize, capitalization: &Capitalization) -> f64 {
+    let per_word = (word_count as f64).log2() + if *capitalization == Capitalization::Random { 1.0 } else { 0.0 };
+    words as f64 * per_word
+}
+
+impl Runnable for PasswordArgs {
+    fn run(&self, base_args: &BaseArgs, get_input: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        if let Some(command) = &self.command {
+            return command.run(base_args, get_input);
+        }
+
+        let mut rng = base_args.rng();
+        let (passwords, entropy) = match self.passphrase {
+            true => {
+                if self.words == 0 {
+                    return Err("a passphrase needs at least one word".into());
+                }
+                let words = wordlist();
+                let passwords: Vec<String> = (0..self.count).map(|_| self.generate_passphrase(&words, &mut *rng)).collect();
+                (passwords, passphrase_entropy(words.len(), self.words, &self.capitalize))
+            },
+            false => {
+                let classes = self.character_classes()?;
+                let passwords: Vec<String> = (0..self.count).map(|_| self.generate_password(&classes, &mut *rng)).collect();
+                (passwords, password_entropy(&classes, self.length))
+            },
+        };
+
+        if self.json {
+            let results: Vec<_> = passwords
+                .iter()
+                .map(|p| json!({ "password": p, "entropy_bits": (entropy * 10.0).round() / 10.0 }))
+                .collect();
+            return Ok(serde_json::to_string_pretty(&results)?);
+        }
+
+        let lines: Vec<String> = match self.quiet {
+            true => passwords,
+            false => passwords.iter().map(|p| format!("{}\t{:.1} bits", p, entropy)).collect(),
+        };
+        Ok(lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::cli::{
+        args::{BaseArgs, Runnable},
+        ArgsEnum,
+    };
+
+    use super::{Capitalization, CharacterClass, PasswordArgs, AMBIGUOUS, SYMBOLS};
+
+    fn args() -> PasswordArgs {
+        PasswordArgs {
+            count: 1,
+            length: 16,
+            classes: vec![CharacterClass::Upper, CharacterClass::Lower, CharacterClass::Digits, CharacterClass::Symbols],
+            symbols: String::from(SYMBOLS),
+            exclude_ambiguous: false,
+            passphrase: false,
+            words: 6,
+            separator: String::from("-"),
+            capitalize: Capitalization::None,
+            quiet: true,
+            json: false,
+            command: None,
+        }
+    }
+
+    fn run(sut: PasswordArgs) -> Result<String, Box<dyn std::error::Error>> {
+        let base_args = BaseArgs::fixture(ArgsEnum::Password(sut.clone()));
+        sut.run(&base_args, String::new)
+    }
+
+    #[test]
+    fn will_include_every_character_class() {
+        let sut = PasswordArgs { count: 200, length: 4, exclude_ambiguous: true, ..args() };
+        let result = run(sut).unwrap();
+
+        assert_eq!(result.lines().count(), 200);
+        for password in result.lines() {
+            assert_eq!(password.chars().count(), 4);
+            assert!(password.chars().any(|c| c.is_ascii_uppercase()));
+            assert!(password.chars().any(|c| c.is_ascii_lowercase()));
+            assert!(password.chars().any(|c| c.is_ascii_digit()));
+            assert!(password.chars().any(|c| SYMBOLS.contains(c)));
+            assert!(!password.chars().any(|c| AMBIGUOUS.contains(c)));
+        }
+    }
+
+    #[rstest(length, classes, symbols, expected_result,
+        case(16, vec![CharacterClass::Upper, CharacterClass::Lower, CharacterClass::Digits, CharacterClass::Symbols], SYMBOLS, "104.6 bits"),
+        case(1, vec![CharacterClass::Digits], SYMBOLS, "3.3 bits"),
+        // the same class twice only counts once, and one character has no entropy
+        case(2, vec![CharacterClass::Symbols, CharacterClass::Symbols], "!", "0.0 bits"),
+    )]
+    fn will_report_password_entropy(length: usize, classes: Vec<CharacterClass>, symbols: &str, expected_result: &str) {
+        let sut = PasswordArgs { length, classes, symbols: String::from(symbols), quiet: false, ..args() };
+        assert!(run(sut).unwrap().ends_with(&format!("\t{}", expected_result)));
+    }
+
+    #[rstest(length, classes, symbols,
+        case(2, vec![CharacterClass::Upper, CharacterClass::Lower, CharacterClass::Digits], SYMBOLS),
+        case(16, vec![CharacterClass::Symbols], "abc"),
+        case(16, vec![CharacterClass::Symbols], "|"),
+    )]
+    fn will_reject_invalid_policy(length: usize, classes: Vec<CharacterClass>, symbols: &str) {
+        let sut = PasswordArgs { length, classes, symbols: String::from(symbols), exclude_ambiguous: true, ..args() };
+        assert!(run(sut).is_err());
+    }
+
+    #[rstest(capitalize, expected_result,
+        case(Capitalization::None, "77.5 bits"),
+        case(Capitalization::Random, "83.5 bits"),
+    )]
+    fn will_generate_passphrase(capitalize: Capitalization, expected_result: &str) {
+        let sut = PasswordArgs { passphrase: true, separator: String::from(" "), capitalize, quiet: false, ..args() };
+        let result = run(sut).unwrap();
+        let (passphrase, entropy) = result.split_once('\t').unwrap();
+
+        let words = super::wordlist();
+        assert_eq!(words.len(), 7776);
+        assert_eq!(passphrase.split(' ').count(), 6);
+        assert!(passphrase.split(' ').all(|w| words.contains(&w.to_lowercase().as_str())));
+        assert_eq!(entropy, expected_result);
+    }
+
+    #[test]
+    fn will_generate_same_password_for_same_seed() {
+        assert_eq!(run(args()).unwrap(), "P|'u]-m8uB)'|7^.");
+    }
+}
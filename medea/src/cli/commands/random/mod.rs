@@ -0,0 +1,418 @@
+use std::io::Write;
+
+use crate::cli::utils::{
+    base32_utils, base58_utils, base64_utils, hex_utils,
+    literal_utils::{Language, LiteralWriter},
+    regex_utils::RegexGenerator, size_utils,
+};
+
+use super::super::{BaseArgs, Runnable};
+use clap::{Parser, Subcommand, ValueEnum};
+
+use enum_dispatch::enum_dispatch;
+use indoc::indoc;
+use rand::Rng;
+
+pub mod number;
+pub mod pick;
+
+use number::{DiceArgs, FloatArgs, IntArgs};
+use pick::{ChoiceArgs, SampleArgs, ShuffleArgs};
+
+#[derive(Parser, Debug, Clone)]
+#[command(
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true,
+    about = "Generate random bytes, strings, numbers and picks",
+    after_help = "See `medea help timestamp` for details",
+    after_long_help = indoc!{r#"
+        Examples:
+            # generate 32 random bytes and output as uppercase hex string
+            medea rnd -f hex -u 32
+
+            # generate 128 random bytes and output as base64 string
+            medea rnd -f b64 128
+
+            # generate a 32 byte key as a rust array
+            medea rnd -t rust 32
+
+            # write 1 kibibyte of raw bytes to a file
+            medea --trim rnd -t raw 1Ki > random.bin
+
+            # generate 5 strings matching a regular expression
+            medea rnd --regex '[A-Z]{3}-\d{4}' 5
+
+            # roll 10 numbers between 1 and 100
+            medea rnd int 1..100 -n 10
+
+            # pick 5 random lines of a file
+            medea rnd sample -k 5 < names.txt
+
+    "#}
+)]
+
+pub struct RandomArgs {
+    #[arg(
+        short,
+        long,
+        help = "Output format",
+        value_enum,
+        default_value = "hex",
+        value_name = "FORMAT"
+    )]
+    to: Format,
+
+    #[arg(
+        short,
+        long,
+        help = "Use upper case characters for hex output",
+        default_value = "false"
+    )]
+    upper: bool,
+
+    #[arg(
+        short,
+        long,
+        default_value = "80",
+        value_name = "COLUMNS",
+        help = "Maximum line width of c, rust, go, python and dec-list output, 0 for no wrapping"
+    )]
+    width: usize,
+
+    #[arg(
+        long,
+        value_name = "PATTERN",
+        help = "Generate strings matching a regular expression instead of bytes",
+        long_help = indoc!{"
+            Generate strings matching a regular expression instead of bytes, one
+            per line. Supports the syntax of the regex crate, except for word
+            boundaries. Look-around is not supported by the syntax at all.
+        "}
+    )]
+    regex: Option<String>,
+
+    #[arg(
+        long,
+        default_value = "8",
+        value_name = "N",
+        help = "Maximum number of extra repeats for unbounded repetitions like *, + and {n,}",
+        requires = "regex"
+    )]
+    max_repeat: u32,
+
+    #[arg(
+        long,
+        default_value = "false",
+        help = "Draw from every character a class matches, instead of preferring printable ascii",
+        requires = "regex"
+    )]
+    unicode: bool,
+
+    #[arg(
+        help = "Number of bytes to generate",
+        long_help = indoc!{"
+            Number of bytes to generate, or number of strings with --regex.
+            Accepts suffixes like 10k, 10M or 1Gi
+        "},
+        value_parser = size_utils::parse_size,
+        required_unless_present = "regex"
+    )]
+    count: Option<u64>,
+
+    #[command(subcommand)]
+    command: Option<RandomCommand>,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+#[enum_dispatch(Runnable)]
+enum RandomCommand {
+    Int(IntArgs),
+    Float(FloatArgs),
+    Dice(DiceArgs),
+    Choice(ChoiceArgs),
+    Shuffle(ShuffleArgs),
+    Sample(SampleArgs),
+}
+
+// base58 encoding takes time quadratic in the length of its input
+const MAX_B58_BYTES: u64 = 1 << 12;
+
+// a multiple of 3 and 5, so base64 and base32 chunks never need padding
+// until the last one
+const CHUNK_BYTES: u64 = 15 << 12;
+
+#[derive(ValueEnum, Debug, Clone)]
+pub enum Format {
+    Hex,
+    B64,
+    B32,
+    #[value(help = "Bitcoin base58, for up to 4096 bytes")]
+    B58,
+    #[value(help = "C uint8_t array initializer")]
+    C,
+    #[value(help = "Rust [u8; N] array")]
+    Rust,
+    #[value(help = "Go []byte literal")]
+    Go,
+    #[value(help = "Python bytes literal")]
+    Python,
+    #[value(help = "Comma-separated list of decimal bytes")]
+    DecList,
+    #[value(help = "The bytes as they are. Combine with --trim to leave out the trailing newline")]
+    Raw,
+}
+
+impl Format {
+    fn language(&self) -> Option<Language> {
+        match self {
+            Format::C => Some(Language::C),
+            Format::Rust => Some(Language::Rust),
+            Format::Go => Some(Language::Go),
+            Format::Python => Some(Language::Python),
+            Format::DecList => Some(Language::DecList),
+            _ => None,
+        }
+    }
+}
+
+impl Runnable for RandomArgs {
+    fn run(
+        &self,
+        base_args: &BaseArgs,
+        get_input: impl Fn() -> String,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        if let Some(command) = &self.command {
+            return command.run(base_args, get_input);
+        }
+        if let (Format::Raw, None) = (&self.to, &self.regex) {
+            return Err("raw output is binary and can only be written to stdout, use another format here".into());
+        }
+
+        let mut buffer = Vec::new();
+        self.run_to(base_args, String::new, &mut buffer)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+
+    fn run_to(
+        &self,
+        base_args: &BaseArgs,
+        get_input: impl Fn() -> String,
+        out: &mut dyn Write,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(command) = &self.command {
+            return command.run_to(base_args, get_input, out);
+        }
+
+        let mut rng = base_args.rng();
+        if let Some(pattern) = &self.regex {
+            let generator = RegexGenerator::new(pattern, self.max_repeat, self.unicode)?;
+            for i in 0..self.count.unwrap_or(1) {
+                if i > 0 {
+                    out.write_all(b"\n")?;
+                }
+                out.write_all(generator.generate(&mut *rng)?.as_bytes())?;
+            }
+            return Ok(());
+        }
+
+        let count = self.count.unwrap_or_default();
+        // base58 treats the bytes as one big number, so it can't be chunked
+        if let Format::B58 = self.to {
+            if count > MAX_B58_BYTES {
+                return Err(format!("b58 treats the bytes as one number, so it is limited to {} bytes", MAX_B58_BYTES).into());
+            }
+            let random_bytes: Vec<u8> = (0..count).map(|_| rng.gen::<u8>()).collect();
+            out.write_all(base58_utils::encode(&random_bytes).as_bytes())?;
+            return Ok(());
+        }
+
+        let mut literal = self.to.language().map(|language| LiteralWriter::new(language, self.width, self.upper));
+        if let Some(literal) = &literal {
+            literal.begin(out, count)?;
+        }
+
+        let mut remaining = count;
+        let mut random_bytes = Vec::with_capacity(CHUNK_BYTES.min(remaining) as usize);
+        while remaining > 0 {
+            let chunk = CHUNK_BYTES.min(remaining);
+            random_bytes.clear();
+            random_bytes.extend((0..chunk).map(|_| rng.gen::<u8>()));
+            match (&mut literal, &self.to) {
+                (Some(literal), _) => literal.write(out, &random_bytes)?,
+                (None, Format::Raw) => out.write_all(&random_bytes)?,
+                (None, Format::B64) => out.write_all(base64_utils::encode(&random_bytes).as_bytes())?,
+                (None, Format::B32) => out.write_all(base32_utils::encode(&random_bytes, true).as_bytes())?,
+                (None, _) => out.write_all(hex_utils::encode(&random_bytes, self.upper).as_bytes())?,
+            }
+            remaining -= chunk;
+        }
+
+        if let Some(literal) = &mut literal {
+            literal.finish(out)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use rand::Rng;
+    use rstest::rstest;
+
+    use crate::cli::{
+        args::{BaseArgs, Runnable},
+        ArgsEnum,
+    };
+
+    use super::{Format, RandomArgs};
+
+    #[test]
+    fn will_stream_base64_across_chunks() {
+        let sut = RandomArgs {
+            to: Format::B64,
+            upper: false,
+            width: 80,
+            regex: None,
+            max_repeat: 8,
+            unicode: false,
+            command: None,
+            count: Some(super::CHUNK_BYTES * 2 + 1),
+        };
+        let base_args = BaseArgs::fixture(ArgsEnum::Random(sut.clone()));
+
+        let result = sut.run(&base_args, String::new).unwrap();
+        let decoded = crate::cli::utils::base64_utils::decode(&result);
+        assert_eq!(decoded.len() as u64, super::CHUNK_BYTES * 2 + 1);
+        assert!(result.ends_with("=="));
+        assert_eq!(result.matches('=').count(), 2);
+    }
+
+    #[test]
+    fn will_generate_same_bytes_for_same_seed() {
+        let sut = RandomArgs {
+            to: Format::Hex,
+            upper: false,
+            width: 80,
+            regex: None,
+            max_repeat: 8,
+            unicode: false,
+            command: None,
+            count: Some(16),
+        };
+        let base_args = BaseArgs::fixture(ArgsEnum::Random(sut.clone()));
+
+        let result = sut.run(&base_args, String::new).unwrap();
+        assert_eq!(result, "0d995e4be0ca918eb04034eae7d6f3be");
+    }
+
+    #[test]
+    fn will_generate_strings_matching_regex() {
+        let sut = RandomArgs {
+            to: Format::Hex,
+            upper: false,
+            width: 80,
+            regex: Some(String::from(r"[A-Z]{3}-\d{4}")),
+            max_repeat: 8,
+            unicode: false,
+            command: None,
+            count: Some(5),
+        };
+        let base_args = BaseArgs::fixture(ArgsEnum::Random(sut.clone()));
+
+        let result = sut.run(&base_args, String::new).unwrap();
+        let matcher = regex::Regex::new(r"^[A-Z]{3}-\d{4}$").unwrap();
+        assert_eq!(result.lines().count(), 5);
+        assert!(result.lines().all(|line| matcher.is_match(line)), "{}", result);
+    }
+
+    #[test]
+    fn will_generate_same_strings_for_same_seed() {
+        let base_args = BaseArgs::try_parse_from(["medea", "--seed", "fixture", "rnd", "--regex", r"[A-Z]{3}-\d{4}|(foo|bar)+", "4"]).unwrap();
+        let result = base_args.command.run(&base_args, String::new).unwrap();
+        assert_eq!(result, "XBE-0977\nZMU-7466\nbar\nbarbarfoofoobarbarfoofoofoo");
+    }
+
+    fn run(args: &[&str]) -> String {
+        let base_args = BaseArgs::try_parse_from([&["medea", "--seed", "fixture", "rnd"], args].concat()).unwrap();
+        base_args.command.run(&base_args, String::new).unwrap()
+    }
+
+    #[rstest(format, expected_result,
+        case("rust", "let data: [u8; 4] = [\n    0x0d, 0x99, 0x5e, 0x4b,\n];"),
+        case("c", "uint8_t data[4] = {\n    0x0d, 0x99, 0x5e, 0x4b,\n};"),
+        case("go", "data := []byte{\n    0x0d, 0x99, 0x5e, 0x4b,\n}"),
+        case("python", "data = (\n    b\"\\x0d\\x99\\x5e\\x4b\"\n)"),
+        case("dec-list", "13, 153, 94, 75"),
+        case("b32", "BWMV4SY="),
+        case("b58", "MAMX8"),
+    )]
+    fn will_format_bytes(format: &str, expected_result: &str) {
+        assert_eq!(run(&["-t", format, "4"]), expected_result);
+    }
+
+    #[test]
+    fn will_stream_literals_across_chunks() {
+        let count = super::CHUNK_BYTES + 1;
+        let result = run(&["-t", "c", "-w", "0", &count.to_string()]);
+        let lines: Vec<&str> = result.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1].matches("0x").count() as u64, count);
+        assert!(lines[1].ends_with(','));
+        assert!(!lines[1].contains(",,"));
+    }
+
+    #[test]
+    fn will_stream_base32_across_chunks() {
+        let result = run(&["-t", "b32", &(super::CHUNK_BYTES + 1).to_string()]);
+        let decoded = crate::cli::utils::base32_utils::decode(&result).unwrap();
+        assert_eq!(decoded.len() as u64, super::CHUNK_BYTES + 1);
+        assert_eq!(result.matches('=').count(), 6);
+    }
+
+    #[test]
+    fn will_reject_large_base58_output() {
+        let base_args = BaseArgs::try_parse_from(["medea", "rnd", "-t", "b58", "1Gi"]).unwrap();
+        assert!(base_args.command.run(&base_args, String::new).is_err());
+    }
+
+    #[test]
+    fn will_write_raw_bytes_through_run_to() {
+        let base_args = BaseArgs::try_parse_from(["medea", "--seed", "fixture", "rnd", "-t", "raw", "4"]).unwrap();
+        let mut buffer = Vec::new();
+        base_args.command.run_to(&base_args, String::new, &mut buffer).unwrap();
+        assert_eq!(buffer, [0x0d, 0x99, 0x5e, 0x4b]);
+        assert!(base_args.command.run(&base_args, String::new).unwrap_err().to_string().contains("binary"));
+    }
+
+    #[test]
+    fn will_wrap_literals_at_width() {
+        let result = run(&["-t", "dec-list", "-w", "20", "64"]);
+        assert!(result.lines().count() > 1);
+        assert!(result.lines().all(|line| line.len() <= 20), "{}", result);
+    }
+
+    #[test]
+    #[ignore = "benchmark, run with `cargo test --release -- --ignored --nocapture`"]
+    fn bench_streamed_generation() {
+        let count = 1u64 << 26;
+        let base_args = BaseArgs::try_parse_from(["medea", "rnd", &count.to_string()]).unwrap();
+        let ArgsEnum::Random(sut) = &base_args.command else { unreachable!() };
+
+        // the previous approach, collecting every byte before encoding them
+        let start = std::time::Instant::now();
+        {
+            let mut rng = base_args.rng();
+            let random_bytes: Vec<u8> = (0..count).map(|_| rng.gen::<u8>()).collect();
+            let s = crate::cli::utils::hex_utils::encode(&random_bytes, false);
+            assert_eq!(s.len() as u64, count * 2);
+        }
+        let collected = start.elapsed();
+
+        let start = std::time::Instant::now();
+        sut.run_to(&base_args, String::new, &mut std::io::BufWriter::new(std::io::sink())).unwrap();
+        let streamed = start.elapsed();
+
+        println!("{} bytes: collected in {:?}, streamed in {:?}", count, collected, streamed);
+    }
+}
@@ -0,0 +1,368 @@
+use std::collections::HashSet;
+use std::error::Error;
+
+use clap::Parser;
+use indoc::indoc;
+use rand::{seq::index, Rng};
+
+use crate::cli::args::{Runnable, BaseArgs};
+
+#[derive(Parser, Debug, Clone)]
+#[command(
+    about = "Generate random integers in a range",
+    long_about = indoc!{"
+        Generate random integers between MIN and MAX, including both ends.
+        Every integer in the range is equally likely.
+    "},
+    after_long_help = indoc!{r#"
+        Examples:
+            # roll 10 numbers between 1 and 100
+            $ medea rnd int 1..100 -n 10
+
+            # draw 6 different lottery numbers
+            $ medea rnd int 1..49 -n 6 --unique
+    "#}
+)]
+pub struct IntArgs {
+    #[arg(
+        value_name = "MIN..MAX",
+        allow_hyphen_values = true,
+        help = "Range to draw from, including both ends",
+        value_parser = parse_int_range
+    )]
+    range: (i64, i64),
+
+    #[arg(short = 'n', long, default_value = "1", help = "Number of integers to generate")]
+    count: usize,
+
+    #[arg(short, long, default_value = "false", help = "Don't repeat any integer")]
+    unique: bool,
+}
+
+#[derive(Parser, Debug, Clone)]
+#[command(
+    about = "Generate random floating point numbers in a range",
+    long_about = indoc!{"
+        Generate random floating point numbers between MIN and MAX, including
+        MIN but not MAX.
+    "},
+    after_long_help = indoc!{r#"
+        Examples:
+            # generate a number between 0 and 1
+            $ medea rnd float
+
+            # generate 5 temperatures with one decimal place
+            $ medea rnd float -20..40 -n 5 -p 1
+    "#}
+)]
+pub struct FloatArgs {
+    #[arg(
+        value_name = "MIN..MAX",
+        default_value = "0..1",
+        allow_hyphen_values = true,
+        help = "Range to draw from, including MIN but not MAX",
+        value_parser = parse_float_range
+    )]
+    range: (f64, f64),
+
+    #[arg(short = 'n', long, default_value = "1", help = "Number of floats to generate")]
+    count: usize,
+
+    #[arg(short, long, value_name = "DIGITS", help = "Number of decimal places to round to")]
+    precision: Option<usize>,
+}
+
+#[derive(Parser, Debug, Clone)]
+#[command(
+    about = "Roll dice using dice notation",
+    long_about = indoc!{"
+        Roll dice written in dice notation, where NdM rolls N dice with M
+        sides each. Terms can be added and subtracted, and constant terms
+        are added as is. N can be left out to roll a single die.
+    "},
+    after_long_help = indoc!{r#"
+        Examples:
+            # roll three six-sided dice and add two
+            $ medea rnd dice 3d6+2
+            14
+
+            # show the individual rolls
+            $ medea rnd dice 1d20+1d4-1 --rolls
+            16 = [13] + [4] - 1
+    "#}
+)]
+pub struct DiceArgs {
+    #[arg(help = "Dice to roll, like 3d6+2", value_parser = parse_dice)]
+    dice: Dice,
+
+    #[arg(short = 'n', long, default_value = "1", help = "Number of times to roll")]
+    count: usize,
+
+    #[arg(short, long, default_value = "false", help = "Show the individual rolls")]
+    rolls: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Dice(Vec<DiceTerm>);
+
+#[derive(Debug, Clone, PartialEq)]
+enum DiceTerm {
+    Dice { negative: bool, count: u32, sides: u32 },
+    Constant(i64),
+}
+
+// the most dice a single term may roll, to keep typos like 1000000d6 cheap
+const MAX_DICE: u32 = 10_000;
+
+fn split_range(input: &str) -> Result<(&str, &str), String> {
+    input.split_once("..").ok_or_else(|| format!("expected a range like 1..100, found {:?}", input))
+}
+
+fn parse_int_range(input: &str) -> Result<(i64, i64), String> {
+    let (min, max) = split_range(input)?;
+    let min: i64 = min.trim().parse().map_err(|_| format!("invalid minimum {:?}", min))?;
+    let max: i64 = max.trim().parse().map_err(|_| format!("invalid maximum {:?}", max))?;
+    if min > max {
+        return Err(format!("minimum {} is greater than maximum {}", min, max));
+    }
+    Ok((min, max))
+}
+
+fn parse_float_range(input: &str) -> Result<(f64, f64), String> {
+    let (min, max) = split_range(input)?;
+    let min: f64 = min.trim().parse().map_err(|_| format!("invalid minimum {:?}", min))?;
+    let max: f64 = max.trim().parse().map_err(|_| format!("invalid maximum {:?}", max))?;
+    if !(min.is_finite() && max.is_finite() && min < max) {
+        return Err(format!("expected a finite range with minimum less than maximum, found {:?}", input));
+    }
+    // rand samples from min + (max - min) * x, so the width has to fit an f64 too
+    if !(max - min).is_finite() {
+        return Err(format!("range {:?} is too wide, the difference between its ends must fit a float", input));
+    }
+    Ok((min, max))
+}
+
+fn parse_dice(input: &str) -> Result<Dice, String> {
+    let input: String = input.chars().filter(|c| !c.is_whitespace()).collect();
+    let mut terms = Vec::new();
+    let mut rest = input.as_str();
+    let mut negative = false;
+    loop {
+        let end = rest.find(['+', '-']).unwrap_or(rest.len());
+        let term = &rest[..end];
+        let invalid = || format!("invalid dice term {:?} in {:?}", term, input);
+        match term.split_once(['d', 'D']) {
+            Some((count, sides)) => {
+                let count: u32 = match count {
+                    "" => 1,
+                    c => c.parse().map_err(|_| invalid())?,
+                };
+                let sides: u32 = sides.parse().map_err(|_| invalid())?;
+                if count == 0 || sides == 0 {
+                    return Err(invalid());
+                }
+                if count > MAX_DICE {
+                    return Err(format!("can't roll more than {} dice at once", MAX_DICE));
+                }
+                terms.push(DiceTerm::Dice { negative, count, sides });
+            },
+            None => {
+                let constant: i64 = term.parse().map_err(|_| invalid())?;
+                terms.push(DiceTerm::Constant(if negative { -constant } else { constant }));
+            },
+        }
+        if end == rest.len() {
+            return Ok(Dice(terms));
+        }
+        negative = rest[end..].starts_with('-');
+        rest = &rest[end + 1..];
+    }
+}
+
+impl Runnable for IntArgs {
+    fn run(&self, base_args: &BaseArgs, _: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        let (min, max) = self.range;
+        let mut rng = base_args.rng();
+        let numbers: Vec<i64> = match self.unique {
+            false => (0..self.count).map(|_| rng.gen_range(min..=max)).collect(),
+            true => {
+                let size = (i128::from(max) - i128::from(min) + 1) as u128;
+                if self.count as u128 > size {
+                    return Err(format!("can't draw {} unique integers from a range of {}", self.count, size).into());
+                }
+                // dense draws go through rand's index sampling. the range is
+                // at most twice the count here, so it fits in memory
+                match (self.count as u128) * 2 > size {
+                    true => index::sample(&mut *rng, size as usize, self.count)
+                        .into_iter()
+                        .map(|i| (i128::from(min) + i as i128) as i64)
+                        .collect(),
+                    false => {
+                        let mut seen = HashSet::with_capacity(self.count);
+                        let mut numbers = Vec::with_capacity(self.count);
+                        while numbers.len() < self.count {
+                            let number = rng.gen_range(min..=max);
+                            if seen.insert(number) {
+                                numbers.push(number);
+                            }
+                        }
+                        numbers
+                    },
+                }
+            },
+        };
+        Ok(numbers.iter().map(|n| n.to_string()).collect::<Vec<String>>().join("\n"))
+    }
+}
+
+impl Runnable for FloatArgs {
+    fn run(&self, base_args: &BaseArgs, _: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        let (min, max) = self.range;
+        let mut rng = base_args.rng();
+        let numbers: Vec<String> = (0..self.count)
+            .map(|_| rng.gen_range(min..max))
+            .map(|n| match self.precision {
+                Some(precision) => format!("{:.*}", precision, n),
+                None => n.to_string(),
+            })
+            .collect();
+        Ok(numbers.join("\n"))
+    }
+}
+
+impl DiceArgs {
+    fn roll(&self, rng: &mut impl Rng) -> Result<String, Box<dyn Error>> {
+        let mut total: i64 = 0;
+        let mut parts: Vec<String> = Vec::new();
+        for term in &self.dice.0 {
+            let (negative, part) = match term {
+                DiceTerm::Dice { negative, count, sides } => {
+                    let rolls: Vec<u32> = (0..*count).map(|_| rng.gen_range(1..=*sides)).collect();
+                    let sum: i64 = rolls.iter().map(|r| i64::from(*r)).sum();
+                    total = match negative {
+                        true => total.checked_sub(sum),
+                        false => total.checked_add(sum),
+                    }.ok_or("dice total is out of range")?;
+                    let rolls: Vec<String> = rolls.iter().map(|r| r.to_string()).collect();
+                    (*negative, format!("[{}]", rolls.join(", ")))
+                },
+                DiceTerm::Constant(constant) => {
+                    total = total.checked_add(*constant).ok_or("dice total is out of range")?;
+                    (*constant < 0, constant.abs().to_string())
+                },
+            };
+            match (parts.is_empty(), negative) {
+                (true, false) => parts.push(part),
+                (true, true) => parts.push(format!("-{}", part)),
+                (false, false) => parts.push(format!("+ {}", part)),
+                (false, true) => parts.push(format!("- {}", part)),
+            }
+        }
+        Ok(match self.rolls {
+            true => format!("{} = {}", total, parts.join(" ")),
+            false => total.to_string(),
+        })
+    }
+}
+
+impl Runnable for DiceArgs {
+    fn run(&self, base_args: &BaseArgs, _: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        let mut rng = base_args.rng();
+        let rolls: Vec<String> = (0..self.count).map(|_| self.roll(&mut *rng)).collect::<Result<_, _>>()?;
+        Ok(rolls.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use rstest::rstest;
+
+    use crate::cli::args::{BaseArgs, Runnable};
+
+    use super::{parse_dice, Dice, DiceTerm};
+
+    fn run(args: &[&str]) -> Result<String, Box<dyn std::error::Error>> {
+        let base_args = BaseArgs::try_parse_from([&["medea", "--seed", "fixture", "rnd"], args].concat())?;
+        base_args.command.run(&base_args, String::new)
+    }
+
+    #[test]
+    fn will_generate_integers_in_range() {
+        let result = run(&["int", "1..6", "-n", "1000"]).unwrap();
+        let numbers: Vec<i64> = result.lines().map(|l| l.parse().unwrap()).collect();
+        assert_eq!(numbers.len(), 1000);
+        assert!(numbers.iter().all(|n| (1..=6).contains(n)));
+        assert!((1..=6).all(|n| numbers.contains(&n)));
+    }
+
+    #[test]
+    fn will_generate_same_integers_for_same_seed() {
+        assert_eq!(run(&["int", "-100..100", "-n", "3"]).unwrap(), run(&["int", "-100..100", "-n", "3"]).unwrap());
+    }
+
+    #[rstest(range, count,
+        case("1..49", "6"),
+        case("1..10", "10"),
+        case("-9223372036854775808..9223372036854775807", "100"),
+    )]
+    fn will_generate_unique_integers(range: &str, count: &str) {
+        let result = run(&["int", range, "-n", count, "--unique"]).unwrap();
+        let mut numbers: Vec<i64> = result.lines().map(|l| l.parse().unwrap()).collect();
+        numbers.sort();
+        numbers.dedup();
+        assert_eq!(numbers.len().to_string(), count);
+    }
+
+    #[rstest(args,
+        case(&["int", "1..10", "-n", "11", "--unique"]),
+        case(&["int", "10..1"]),
+        case(&["int", "1-10"]),
+        case(&["float", "1..1"]),
+        case(&["dice", "3d0"]),
+        case(&["dice", "3x6"]),
+        case(&["dice", "100000d6"]),
+        case(&["float", "-1e308..1e308"]),
+        case(&["dice", "1d6+9223372036854775807"]),
+        case(&["dice", "1d1-9223372036854775807-3d1"]),
+    )]
+    fn will_reject_invalid_input(args: &[&str]) {
+        assert!(run(args).is_err());
+    }
+
+    #[test]
+    fn will_generate_floats_in_range() {
+        let result = run(&["float", "-1.5..2.5", "-n", "100", "-p", "2"]).unwrap();
+        assert!(result.lines().all(|l| l.split_once('.').unwrap().1.len() == 2));
+        assert!(result.lines().map(|l| l.parse::<f64>().unwrap()).all(|n| (-1.5..=2.5).contains(&n)));
+    }
+
+    #[rstest(input, expected_result,
+        case("3d6+2", vec![
+            DiceTerm::Dice { negative: false, count: 3, sides: 6 },
+            DiceTerm::Constant(2),
+        ]),
+        case("d20 - 1d4 - 1", vec![
+            DiceTerm::Dice { negative: false, count: 1, sides: 20 },
+            DiceTerm::Dice { negative: true, count: 1, sides: 4 },
+            DiceTerm::Constant(-1),
+        ]),
+    )]
+    fn will_parse_dice(input: &str, expected_result: Vec<DiceTerm>) {
+        assert_eq!(parse_dice(input).unwrap(), Dice(expected_result));
+    }
+
+    #[test]
+    fn will_roll_dice() {
+        let result = run(&["dice", "3d6+2", "-n", "200"]).unwrap();
+        let totals: Vec<i64> = result.lines().map(|l| l.parse().unwrap()).collect();
+        assert!(totals.iter().all(|t| (5..=20).contains(t)));
+
+        let result = run(&["dice", "2d6-1", "--rolls"]).unwrap();
+        let (total, rolls) = result.split_once(" = ").unwrap();
+        let rolls: Vec<i64> = rolls.trim_start_matches('[').split(']').next().unwrap().split(", ").map(|r| r.parse().unwrap()).collect();
+        assert!(rolls.iter().all(|r| (1..=6).contains(r)));
+        assert_eq!(total.parse::<i64>().unwrap(), rolls.iter().sum::<i64>() - 1);
+        assert!(result.ends_with("] - 1"));
+    }
+}
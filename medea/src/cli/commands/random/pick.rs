@@ -0,0 +1,180 @@
+use std::collections::HashSet;
+use std::error::Error;
+
+use clap::Parser;
+use indoc::indoc;
+use rand::{seq::SliceRandom, Rng};
+
+use crate::cli::args::{Runnable, BaseArgs};
+
+#[derive(Parser, Debug, Clone)]
+#[command(
+    about = "Pick random items from a list",
+    long_about = indoc!{"
+        Pick random items from the given list, or from the lines of stdin
+        if no items are given. Items are picked with replacement unless
+        --unique is set.
+    "},
+    after_long_help = indoc!{r#"
+        Examples:
+            $ medea rnd choice rock paper scissors
+            paper
+
+            # pick 2 different reviewers
+            $ medea rnd choice -n 2 --unique < reviewers.txt
+    "#}
+)]
+pub struct ChoiceArgs {
+    #[arg(help = "Items to pick from. If omitted, will read lines from stdin")]
+    items: Vec<String>,
+
+    #[arg(short = 'n', long, default_value = "1", help = "Number of items to pick")]
+    count: usize,
+
+    #[arg(short, long, default_value = "false", help = "Don't pick the same item more than once")]
+    unique: bool,
+}
+
+#[derive(Parser, Debug, Clone)]
+#[command(
+    about = "Shuffle the lines of stdin",
+    long_about = indoc!{"
+        Shuffle the lines of stdin, or the given items, into a random order.
+        Every order is equally likely.
+    "},
+    after_long_help = indoc!{r#"
+        Examples:
+            $ seq 5 | medea rnd shuffle
+            3
+            5
+            1
+            4
+            2
+    "#}
+)]
+pub struct ShuffleArgs {
+    #[arg(help = "Items to shuffle. If omitted, will read lines from stdin")]
+    items: Vec<String>,
+}
+
+#[derive(Parser, Debug, Clone)]
+#[command(
+    about = "Sample lines of stdin without replacement",
+    long_about = indoc!{"
+        Pick K different lines of stdin, or of the given items, in a random
+        order. Every subset of K lines is equally likely.
+    "},
+    after_long_help = indoc!{r#"
+        Examples:
+            # spot check 5 lines of a log
+            $ medea rnd sample -k 5 < app.log
+    "#}
+)]
+pub struct SampleArgs {
+    #[arg(help = "Items to sample. If omitted, will read lines from stdin")]
+    items: Vec<String>,
+
+    #[arg(short, help = "Number of items to sample")]
+    k: usize,
+}
+
+fn read_items(items: &[String], get_input: impl Fn() -> String) -> Vec<String> {
+    match items.is_empty() {
+        true => get_input().lines().map(|l| l.to_string()).collect(),
+        false => items.to_vec(),
+    }
+}
+
+fn sample(mut items: Vec<String>, k: usize, rng: &mut impl Rng) -> Result<Vec<String>, Box<dyn Error>> {
+    if k > items.len() {
+        return Err(format!("can't pick {} items from a list of {}", k, items.len()).into());
+    }
+    let (sample, _) = items.partial_shuffle(rng, k);
+    Ok(sample.to_vec())
+}
+
+impl Runnable for ChoiceArgs {
+    fn run(&self, base_args: &BaseArgs, get_input: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        let mut items = read_items(&self.items, get_input);
+        if items.is_empty() {
+            return Err("no items to pick from".into());
+        }
+        let mut rng = base_args.rng();
+        let picked = match self.unique {
+            true => {
+                let mut seen = HashSet::new();
+                items.retain(|i| seen.insert(i.clone()));
+                sample(items, self.count, &mut *rng)?
+            },
+            false => (0..self.count).map(|_| items.choose(&mut *rng).unwrap().clone()).collect(),
+        };
+        Ok(picked.join("\n"))
+    }
+}
+
+impl Runnable for ShuffleArgs {
+    fn run(&self, base_args: &BaseArgs, get_input: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        let mut items = read_items(&self.items, get_input);
+        items.shuffle(&mut *base_args.rng());
+        Ok(items.join("\n"))
+    }
+}
+
+impl Runnable for SampleArgs {
+    fn run(&self, base_args: &BaseArgs, get_input: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        let items = read_items(&self.items, get_input);
+        Ok(sample(items, self.k, &mut *base_args.rng())?.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use crate::cli::args::{BaseArgs, Runnable};
+
+    fn run(args: &[&str], input: &str) -> Result<String, Box<dyn std::error::Error>> {
+        let base_args = BaseArgs::try_parse_from([&["medea", "--seed", "fixture", "rnd"], args].concat())?;
+        base_args.command.run(&base_args, || String::from(input))
+    }
+
+    fn sorted(output: &str) -> Vec<&str> {
+        let mut lines: Vec<&str> = output.lines().collect();
+        lines.sort();
+        lines
+    }
+
+    #[test]
+    fn will_pick_items_with_replacement() {
+        let result = run(&["choice", "a", "b", "-n", "100"], "").unwrap();
+        assert_eq!(result.lines().count(), 100);
+        assert!(result.lines().all(|l| l == "a" || l == "b"));
+    }
+
+    #[test]
+    fn will_pick_unique_items() {
+        let result = run(&["choice", "-n", "3", "--unique"], "a\nb\na\nc\n").unwrap();
+        assert_eq!(sorted(&result), vec!["a", "b", "c"]);
+        assert!(run(&["choice", "-n", "4", "--unique"], "a\nb\na\nc\n").is_err());
+    }
+
+    #[test]
+    fn will_shuffle_lines() {
+        let input = (1..=20).map(|i| i.to_string()).collect::<Vec<String>>().join("\n");
+        let result = run(&["shuffle"], &input).unwrap();
+        assert_ne!(result, input);
+        assert_eq!(sorted(&result), sorted(&input));
+        assert_eq!(result, run(&["shuffle"], &input).unwrap());
+    }
+
+    #[test]
+    fn will_sample_lines() {
+        let input = (1..=20).map(|i| i.to_string()).collect::<Vec<String>>().join("\n");
+        let result = run(&["sample", "-k", "5"], &input).unwrap();
+        let mut lines = sorted(&result);
+        lines.dedup();
+        assert_eq!(lines.len(), 5);
+        assert!(lines.iter().all(|l| input.lines().any(|i| i == *l)));
+        assert!(run(&["sample", "-k", "21"], &input).is_err());
+    }
+}
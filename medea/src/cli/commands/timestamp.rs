@@ -3,7 +3,8 @@ use std::error::Error;
 use super::super::{BaseArgs, Runnable};
 use clap::{Parser, ValueEnum};
 
-use chrono::{DateTime, TimeZone, Utc};
+use chrono::format::{self as chrono_format, Item, Parsed, StrftimeItems};
+use chrono::{DateTime, Datelike, TimeZone, Utc};
 use chrono_tz::Tz;
 use indoc::indoc;
 use regex::Regex;
@@ -17,6 +18,14 @@ use regex::Regex;
         Both iso8601 and unix (epoch) timestamps are supported
         as input, and the type will be parsed automatically.
         Omit the input to use the current time.
+
+        Unix timestamps may be negative or fractional, and may
+        be given in seconds, milliseconds, microseconds or
+        nanoseconds. The unit is guessed from the number of
+        digits: up to 11 digits are seconds, up to 14 are
+        milliseconds, up to 17 are microseconds and anything
+        longer is nanoseconds. Use --unit when the guess is
+        wrong, e.g. for milliseconds before 1973.
     "},
     after_long_help = indoc!{r#"
         Examples:
@@ -32,6 +41,26 @@ use regex::Regex;
             $ medea ts -f unix
             1692580941
 
+            # convert a javascript timestamp, the unit is detected
+            $ medea ts 1692545400123
+            2023-08-20T15:30:00.123+00:00
+
+            # convert fractional seconds to microseconds
+            $ medea ts -t unix-us 1692545400.5
+            1692545400500000
+
+            # convert an apache log time to an http date
+            $ medea ts -i apache-clf -o rfc7231 '13/Feb/2009:23:31:30 +0100'
+            Fri, 13 Feb 2009 22:31:30 GMT
+
+            # read a time without a zone as new york time
+            $ medea ts -i sql --assume-zone America/New_York '2009-02-13 18:31:30'
+            2009-02-13T23:31:30+00:00
+
+            # output a custom format
+            $ medea ts -o '%A, %B %e %Y' 1234567890
+            Friday, February 13 2009
+
     "#}
 )]
 pub struct TimeStampArgs {
@@ -43,13 +72,62 @@ pub struct TimeStampArgs {
             or iso8601 format. If omitted, will
             default to current time.
         "},
-        required = false
+        required = false,
+        allow_negative_numbers = true
     )]
     timestamp: Option<String>,
 
+    #[arg(
+        long,
+        value_enum,
+        help = "Unit of a unix input timestamp",
+        long_help = "Unit of a unix input timestamp. If omitted, the unit is guessed from the number of digits"
+    )]
+    unit: Option<Unit>,
+
+    #[arg(
+        short,
+        long,
+        value_name = "FORMAT",
+        value_parser = parse_time_format,
+        help = "Format of the input, either a preset or a strftime string",
+        long_help = indoc!{"
+            Format of the input, either a preset or a strftime string like
+            '%d/%m/%Y %H:%M'. Presets are apache-clf, syslog, rfc2822,
+            rfc7231 (or http-date), sql and iso-week. Missing fields are
+            filled in: the time defaults to midnight, and the year to the
+            current year, as syslog leaves it out.
+        "}
+    )]
+    input_format: Option<TimeFormat>,
+
+    #[arg(
+        long,
+        value_name = "ZONE",
+        help = "Timezone of inputs that don't include an offset",
+        long_help = "Timezone of inputs that don't include an offset, like sql timestamps. Defaults to UTC",
+        requires = "input_format"
+    )]
+    assume_zone: Option<String>,
+
     #[arg(short = 'z', long, long_help = "Timezone of the output")]
     timezone: Option<String>,
 
+    #[arg(
+        short,
+        long,
+        value_name = "FORMAT",
+        value_parser = parse_time_format,
+        conflicts_with = "to",
+        help = "Format of the output, either a preset or a strftime string",
+        long_help = indoc!{"
+            Format of the output, either a preset or a strftime string like
+            '%d/%m/%Y %H:%M'. Takes the same presets as --input-format.
+            rfc7231 dates are always in GMT, regardless of --timezone
+        "}
+    )]
+    output_format: Option<TimeFormat>,
+
     #[arg(
         short,
         long,
@@ -61,9 +139,118 @@ pub struct TimeStampArgs {
 }
 
 #[derive(ValueEnum, Debug, Clone)]
-enum Format {
+pub enum Format {
     Iso,
-    Unix
+    #[value(help = "Unix time in seconds")]
+    Unix,
+    #[value(help = "Unix time in milliseconds")]
+    UnixMs,
+    #[value(help = "Unix time in microseconds")]
+    UnixUs,
+    #[value(help = "Unix time in nanoseconds")]
+    UnixNs,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum Unit {
+    #[value(help = "Seconds")]
+    S,
+    #[value(help = "Milliseconds")]
+    Ms,
+    #[value(help = "Microseconds")]
+    Us,
+    #[value(help = "Nanoseconds")]
+    Ns,
+}
+
+impl Unit {
+    fn nanos(&self) -> i128 {
+        match self {
+            Unit::S => 1_000_000_000,
+            Unit::Ms => 1_000_000,
+            Unit::Us => 1_000,
+            Unit::Ns => 1,
+        }
+    }
+
+    // seconds reach the year 5138 before they are as long as a millisecond
+    // timestamp from 1973, so the number of digits is a safe guess for any
+    // date people actually use
+    fn detect(digits: &str) -> Unit {
+        match digits.trim_start_matches('0').len() {
+            0..=11 => Unit::S,
+            12..=14 => Unit::Ms,
+            15..=17 => Unit::Us,
+            _ => Unit::Ns,
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum Preset {
+    #[value(help = "Apache common log format, like 13/Feb/2009:23:31:30 +0000")]
+    ApacheClf,
+    #[value(help = "Syslog (rfc3164) time, like Feb 13 23:31:30")]
+    Syslog,
+    #[value(help = "Email date, like Fri, 13 Feb 2009 23:31:30 +0000")]
+    Rfc2822,
+    #[value(alias = "http-date", help = "Http date, like Fri, 13 Feb 2009 23:31:30 GMT")]
+    Rfc7231,
+    #[value(help = "Sql timestamp, like 2009-02-13 23:31:30")]
+    Sql,
+    #[value(help = "Iso 8601 week date, like 2009-W07-5")]
+    IsoWeek,
+}
+
+impl Preset {
+    fn strftime(&self) -> &'static str {
+        match self {
+            Preset::ApacheClf => "%d/%b/%Y:%H:%M:%S %z",
+            Preset::Syslog => "%b %e %H:%M:%S",
+            Preset::Rfc2822 => "%a, %d %b %Y %H:%M:%S %z",
+            Preset::Rfc7231 => "%a, %d %b %Y %H:%M:%S GMT",
+            Preset::Sql => "%Y-%m-%d %H:%M:%S",
+            Preset::IsoWeek => "%G-W%V-%u",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum TimeFormat {
+    Preset(Preset),
+    Strftime(String),
+}
+
+impl TimeFormat {
+    fn strftime(&self) -> &str {
+        match self {
+            TimeFormat::Preset(preset) => preset.strftime(),
+            TimeFormat::Strftime(s) => s,
+        }
+    }
+
+    // the zone the format is always written in, whatever the input or --timezone
+    fn fixed_zone(&self) -> Option<Tz> {
+        match self {
+            TimeFormat::Preset(Preset::Rfc7231) => Some(Tz::UTC),
+            _ => None,
+        }
+    }
+}
+
+// anything with a % is a strftime string, anything else has to be a preset
+fn parse_time_format(input: &str) -> Result<TimeFormat, String> {
+    if !input.contains('%') {
+        return Preset::from_str(input, true)
+            .map(TimeFormat::Preset)
+            .map_err(|_| format!("unknown preset {:?}, use a strftime string or one of: apache-clf, syslog, rfc2822, rfc7231, sql, iso-week", input));
+    }
+
+    // chrono panics while formatting with an invalid specifier, so catch it here
+    if StrftimeItems::new(input).any(|item| matches!(item, Item::Error)) {
+        return Err(format!("invalid strftime string: {:?}", input));
+    }
+    Ok(TimeFormat::Strftime(input.to_string()))
 }
 
 #[derive(Debug)]
@@ -90,45 +277,143 @@ impl std::fmt::Display for TimestampError {
 }
 impl Error for TimestampError {}
 
-impl TimeStampArgs {
-    const NUMERIC_TIMESTAMP_PATTERN: &str = r"^[0-9]+$";
+const NUMERIC_TIMESTAMP_PATTERN: &str = r"^(-)?([0-9]+)(?:\.([0-9]+))?$";
+
+pub fn parse_timestamp(input_string: &str) -> Result<DateTime<Utc>, Box<dyn Error>> {
+    parse_timestamp_in_unit(input_string, None)
+}
+
+pub fn parse_timestamp_in_unit(input_string: &str, unit: Option<Unit>) -> Result<DateTime<Utc>, Box<dyn Error>> {
+    let regex = Regex::new(NUMERIC_TIMESTAMP_PATTERN)?;
+    match regex.captures(input_string) {
+        Some(captures) => {
+            let whole = &captures[2];
+            let unit = unit.unwrap_or_else(|| Unit::detect(whole));
+
+            // anything past nanoseconds is cut off, even for seconds
+            let fraction = captures.get(3).map_or("", |m| m.as_str());
+            let fraction = format!("{:0<9.9}", fraction).parse::<i128>()?;
+
+            let mut nanos = whole.parse::<i128>().map_err(|_| "timestamp out of range")?
+                .checked_mul(unit.nanos())
+                .ok_or("timestamp out of range")?
+                + fraction * unit.nanos() / 1_000_000_000;
+            if captures.get(1).is_some() {
+                nanos = -nanos;
+            }
+
+            let secs = i64::try_from(nanos.div_euclid(1_000_000_000)).map_err(|_| "timestamp out of range")?;
+            let nsecs = nanos.rem_euclid(1_000_000_000) as u32;
+            Ok(Utc.timestamp_opt(secs, nsecs).single().ok_or("timestamp out of range")?)
+        }
+        None => Ok(DateTime::parse_from_str(input_string, "%+")
+            .map_err(TimestampError::ParseError)?.with_timezone(&Utc)),
+    }
+}
+
+pub fn parse_timestamp_with_format(
+    input_string: &str,
+    format: &TimeFormat,
+    assume_zone: &Tz,
+    now: &DateTime<Utc>,
+) -> Result<DateTime<Utc>, Box<dyn Error>> {
+    // chrono's own rfc2822 parser also takes the obsolete zone names like GMT
+    if let TimeFormat::Preset(Preset::Rfc2822) = format {
+        return Ok(DateTime::parse_from_rfc2822(input_string)
+            .map_err(TimestampError::ParseError)?.with_timezone(&Utc));
+    }
 
+    let mut parsed = Parsed::new();
+    chrono_format::parse(&mut parsed, input_string, StrftimeItems::new(format.strftime()))
+        .map_err(TimestampError::ParseError)?;
+
+    if parsed.year().is_none() && parsed.year_mod_100().is_none() && parsed.isoyear().is_none() && parsed.timestamp().is_none() {
+        parsed.set_year(i64::from(now.with_timezone(assume_zone).year()))?;
+    }
+    if parsed.hour_div_12().is_none() && parsed.timestamp().is_none() {
+        parsed.set_hour(0)?;
+    }
+    if parsed.minute().is_none() && parsed.timestamp().is_none() {
+        parsed.set_minute(0)?;
+    }
+
+    if parsed.offset().is_some() {
+        return Ok(parsed.to_datetime().map_err(TimestampError::ParseError)?.with_timezone(&Utc));
+    }
+
+    let zone = match (parsed.timestamp(), format.fixed_zone()) {
+        (Some(_), _) => Tz::UTC,
+        (None, Some(zone)) => zone,
+        (None, None) => *assume_zone,
+    };
+
+    let naive = parsed.to_naive_datetime_with_offset(0).map_err(TimestampError::ParseError)?;
+    // the earlier of the two readings when clocks go back
+    let local = zone
+        .from_local_datetime(&naive)
+        .earliest()
+        .ok_or_else(|| format!("{} does not exist in {}", naive, zone))?;
+    Ok(local.with_timezone(&Utc))
+}
+
+fn format_in_zone(ts: &DateTime<Utc>, format_str: &str, timezone: &Option<String>) -> Result<String, Box<dyn Error>> {
+    let output = match timezone {
+        Some(t) =>  {
+            ts
+            .with_timezone(&t.parse::<Tz>().map_err(TimestampError::InvalidTimeZoneError)?)
+            .format(format_str)
+            .to_string()
+        },
+        None => ts.format(format_str).to_string(),
+    };
+
+    Ok(output)
+}
+
+pub fn format_timestamp_with_format(ts: &DateTime<Utc>, format: &TimeFormat, timezone: &Option<String>) -> Result<String, Box<dyn Error>> {
+    match format.fixed_zone() {
+        Some(zone) => Ok(ts.with_timezone(&zone).format(format.strftime()).to_string()),
+        None => format_in_zone(ts, format.strftime(), timezone),
+    }
+}
+
+pub fn format_timestamp(ts: &DateTime<Utc>, to: &Format, timezone: &Option<String>) -> Result<String, Box<dyn Error>> {
+    let output = match (to, timezone) {
+        (Format::Unix, _) => ts.timestamp().to_string(),
+        (Format::UnixMs, _) => ts.timestamp_millis().to_string(),
+        (Format::UnixUs, _) => ts.timestamp_micros().to_string(),
+        (Format::UnixNs, _) => ts
+            .timestamp_nanos_opt()
+            .ok_or("timestamp out of range for nanoseconds, which only cover the years 1677 to 2262")?
+            .to_string(),
+        (Format::Iso, _) => format_in_zone(ts, "%+", timezone)?,
+    };
+
+    Ok(output)
+}
+
+impl TimeStampArgs {
     fn inner_run(
         &self,
-        _: &BaseArgs,
+        base_args: &BaseArgs,
         _: impl Fn() -> String,
     ) -> Result<String, Box<dyn Error>> {
-        let ts = match &self.timestamp {
-            Some(input_string) => {
-                let regex = Regex::new(Self::NUMERIC_TIMESTAMP_PATTERN)?;
-                if regex.is_match(&input_string) {
-                    let secs = input_string.parse::<i64>()?;
-                    Utc.timestamp_opt(secs, 0).unwrap()
-                } else {
-                    DateTime::parse_from_str(&input_string.as_str(), "%+")
-                        .map_err(|e| TimestampError::ParseError(e))?.with_timezone(&Utc)
-                }
-
-            },
-            None => Utc::now()
-        };
-
-        let format_str = match self.to {
-            Format::Unix => "%s",
-            Format::Iso => "%+",
-        };
-
-        let output = match &self.timezone {
-            Some(t) =>  {
-                ts
-                .with_timezone(&t.parse::<Tz>().map_err(|e| TimestampError::InvalidTimeZoneError(e))?)
-                .format(format_str)
-                .to_string()
-            },
-            None => ts.format(format_str).to_string(),
+        let ts = match (&self.timestamp, &self.input_format) {
+            (Some(input_string), Some(format)) => {
+                let assume_zone = match &self.assume_zone {
+                    Some(zone) => zone.parse::<Tz>().map_err(TimestampError::InvalidTimeZoneError)?,
+                    None => Tz::UTC,
+                };
+                parse_timestamp_with_format(input_string, format, &assume_zone, &base_args.now()?)?
+            }
+            (Some(input_string), None) => parse_timestamp_in_unit(input_string, self.unit)?,
+            (None, _) => base_args.now()?
         };
 
-        return Ok(output);
+        match &self.output_format {
+            Some(format) => format_timestamp_with_format(&ts, format, &self.timezone),
+            None => format_timestamp(&ts, &self.to, &self.timezone),
+        }
     }
 }
 
@@ -146,18 +431,18 @@ impl Runnable for TimeStampArgs {
 mod tests {
     use std::error::Error;
 
+    use clap::Parser;
+    use rstest::rstest;
+
     use crate::cli::{
         args::{BaseArgs, Runnable},
         ArgsEnum,
     };
 
-    use super::TimeStampArgs;
+    use super::{Format, TimeStampArgs, Unit};
 
     fn base_args(tsa: TimeStampArgs) -> BaseArgs {
-        BaseArgs {
-            trim: false,
-            command: ArgsEnum::Timestamp(tsa),
-        }
+        BaseArgs::fixture(ArgsEnum::Timestamp(tsa))
     }
 
     fn spoof_input(input: String) -> Box<dyn Fn() -> String> {
@@ -173,6 +458,10 @@ mod tests {
         let sut = TimeStampArgs {
             timezone: None,
             to: super::Format::Iso,
+            unit: None,
+            input_format: None,
+            assume_zone: None,
+            output_format: None,
             timestamp: None,
         };
 
@@ -181,11 +470,36 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn will_use_now_from_base_args() -> Result<(), Box<dyn Error>> {
+        let sut = TimeStampArgs {
+            timezone: None,
+            to: super::Format::Iso,
+            unit: None,
+            input_format: None,
+            assume_zone: None,
+            output_format: None,
+            timestamp: None,
+        };
+        let base_args = BaseArgs {
+            now: Some(String::from("1234567890")),
+            ..base_args(sut.clone())
+        };
+
+        let ts = sut.run(&base_args, spoof_input(String::new()))?;
+        assert_eq!(ts, "2009-02-13T23:31:30+00:00");
+        Ok(())
+    }
+
     #[test]
     fn will_convert_from_unix_time() -> Result<(), Box<dyn Error>> {
         let sut = TimeStampArgs {
             timezone: None,
             to: super::Format::Iso,
+            unit: None,
+            input_format: None,
+            assume_zone: None,
+            output_format: None,
             timestamp: Some(String::from("1234567890")),
         };
 
@@ -199,6 +513,10 @@ mod tests {
         let sut = TimeStampArgs {
             timezone: None,
             to: super::Format::Unix,
+            unit: None,
+            input_format: None,
+            assume_zone: None,
+            output_format: None,
             timestamp: Some(String::from("2009-02-13T23:31:30+03:00")),
         };
 
@@ -212,6 +530,10 @@ mod tests {
         let sut = TimeStampArgs {
             timezone: Some(String::from("EST")),
             to: super::Format::Iso,
+            unit: None,
+            input_format: None,
+            assume_zone: None,
+            output_format: None,
             timestamp: Some(String::from("2009-02-13T23:31:30+02:00")),
         };
 
@@ -225,6 +547,10 @@ mod tests {
         let sut = TimeStampArgs {
             timezone: Some(String::from("America/Toronto")),
             to: super::Format::Iso,
+            unit: None,
+            input_format: None,
+            assume_zone: None,
+            output_format: None,
             timestamp: Some(String::from("2009-02-13T23:31:30+02:00")),
         };
 
@@ -233,5 +559,124 @@ mod tests {
         Ok(())
     }
 
+    #[rstest(input, expected_result,
+        case("1692545400", "2023-08-20T15:30:00+00:00"),
+        case("1692545400123", "2023-08-20T15:30:00.123+00:00"),
+        case("1692545400123456", "2023-08-20T15:30:00.123456+00:00"),
+        case("1692545400123456789", "2023-08-20T15:30:00.123456789+00:00"),
+        case("1692545400.5", "2023-08-20T15:30:00.500+00:00"),
+        case("-1", "1969-12-31T23:59:59+00:00"),
+        case("-1.25", "1969-12-31T23:59:58.750+00:00"),
+        case("0", "1970-01-01T00:00:00+00:00"),
+    )]
+    fn will_detect_unix_units(input: &str, expected_result: &str) {
+        let ts = super::parse_timestamp(input).unwrap();
+        assert_eq!(ts.format("%+").to_string(), expected_result);
+    }
+
+    #[rstest(input, unit, expected_result,
+        case("1000", Unit::Ms, "1970-01-01T00:00:01+00:00"),
+        case("1692545400123", Unit::S, "+55604-08-17T20:02:03+00:00"),
+        case("1.5", Unit::Us, "1970-01-01T00:00:00.000001500+00:00"),
+    )]
+    fn will_use_given_unit(input: &str, unit: Unit, expected_result: &str) {
+        let ts = super::parse_timestamp_in_unit(input, Some(unit)).unwrap();
+        assert_eq!(ts.format("%+").to_string(), expected_result);
+    }
+
+    #[rstest(input,
+        case("99999999999999999999999999999999999999999999"),
+        case("9999999999999999999"),
+        case("1.2.3"),
+    )]
+    fn will_reject_invalid_unix_timestamps(input: &str) {
+        assert!(super::parse_timestamp_in_unit(input, Some(Unit::S)).is_err());
+    }
+
+    #[rstest(to, expected_result,
+        case(Format::Unix, "-2"),
+        case(Format::UnixMs, "-1250"),
+        case(Format::UnixUs, "-1250000"),
+        case(Format::UnixNs, "-1250000000"),
+    )]
+    fn will_convert_to_unix_units(to: Format, expected_result: &str) {
+        let ts = super::parse_timestamp("-1.25").unwrap();
+        assert_eq!(super::format_timestamp(&ts, &to, &None).unwrap(), expected_result);
+    }
+
+    #[test]
+    fn will_reject_nanoseconds_out_of_range() {
+        let ts = super::parse_timestamp("99999999999").unwrap();
+        assert!(super::format_timestamp(&ts, &Format::UnixNs, &None).is_err());
+    }
+
+    #[test]
+    fn will_accept_negative_timestamp_argument() {
+        let base_args = BaseArgs::try_parse_from(["medea", "ts", "-t", "unix-ms", "-1692545400"]).unwrap();
+        let ts = base_args.command.run(&base_args, String::new).unwrap();
+        assert_eq!(ts, "-1692545400000");
+    }
+
+    fn run_args(args: &[&str]) -> Result<String, Box<dyn Error>> {
+        let base_args = BaseArgs::try_parse_from([&["medea", "--now", "2024-06-01T00:00:00Z", "ts"], args].concat())?;
+        base_args.command.run(&base_args, String::new)
+    }
+
+    #[rstest(format, input, expected_result,
+        case("apache-clf", "13/Feb/2009:23:31:30 +0100", "2009-02-13T22:31:30+00:00"),
+        case("syslog", "Feb 13 23:31:30", "2024-02-13T23:31:30+00:00"),
+        case("syslog", "Feb  3 23:31:30", "2024-02-03T23:31:30+00:00"),
+        case("rfc2822", "Fri, 13 Feb 2009 23:31:30 +0000", "2009-02-13T23:31:30+00:00"),
+        case("rfc2822", "13 Feb 2009 23:31:30 GMT", "2009-02-13T23:31:30+00:00"),
+        case("rfc7231", "Fri, 13 Feb 2009 23:31:30 GMT", "2009-02-13T23:31:30+00:00"),
+        case("http-date", "Fri, 13 Feb 2009 23:31:30 GMT", "2009-02-13T23:31:30+00:00"),
+        case("sql", "2009-02-13 23:31:30", "2009-02-13T23:31:30+00:00"),
+        case("iso-week", "2009-W07-5", "2009-02-13T00:00:00+00:00"),
+        case("%d.%m.%Y", "13.02.2009", "2009-02-13T00:00:00+00:00"),
+        case("%s", "1234567890", "2009-02-13T23:31:30+00:00"),
+    )]
+    fn will_parse_input_format(format: &str, input: &str, expected_result: &str) {
+        assert_eq!(run_args(&["-i", format, input]).unwrap(), expected_result);
+    }
+
+    #[rstest(format, expected_result,
+        case("apache-clf", "13/Feb/2009:18:31:30 -0500"),
+        case("syslog", "Feb 13 18:31:30"),
+        case("rfc2822", "Fri, 13 Feb 2009 18:31:30 -0500"),
+        case("rfc7231", "Fri, 13 Feb 2009 23:31:30 GMT"),
+        case("sql", "2009-02-13 18:31:30"),
+        case("iso-week", "2009-W07-5"),
+        case("%A, %B %e %Y", "Friday, February 13 2009"),
+    )]
+    fn will_write_output_format(format: &str, expected_result: &str) {
+        assert_eq!(run_args(&["-o", format, "-z", "America/New_York", "1234567890"]).unwrap(), expected_result);
+    }
 
+    #[rstest(zone, input, expected_result,
+        case("America/New_York", "2009-02-13 18:31:30", "2009-02-13T23:31:30+00:00"),
+        case("Europe/Berlin", "2009-07-01 12:00:00", "2009-07-01T10:00:00+00:00"),
+        // the earlier reading, while clocks go back from 2:59 to 2:00
+        case("Europe/Berlin", "2009-10-25 02:30:00", "2009-10-25T00:30:00+00:00"),
+    )]
+    fn will_assume_zone_for_naive_input(zone: &str, input: &str, expected_result: &str) {
+        assert_eq!(run_args(&["-i", "sql", "--assume-zone", zone, input]).unwrap(), expected_result);
+    }
+
+    #[test]
+    fn will_ignore_assumed_zone_for_input_with_offset() {
+        let result = run_args(&["-i", "apache-clf", "--assume-zone", "Asia/Tokyo", "13/Feb/2009:23:31:30 +0000"]).unwrap();
+        assert_eq!(result, "2009-02-13T23:31:30+00:00");
+    }
+
+    #[rstest(args,
+        case(&["-i", "sql", "--assume-zone", "Europe/Berlin", "2009-03-29 02:30:00"]),
+        case(&["-i", "sql", "2009-02-13T23:31:30"]),
+        case(&["-i", "not-a-preset", "2009-02-13"]),
+        case(&["-o", "%Q", "1234567890"]),
+        case(&["-o", "sql", "-t", "unix", "1234567890"]),
+        case(&["--assume-zone", "UTC", "1234567890"]),
+    )]
+    fn will_reject_invalid_formats(args: &[&str]) {
+        assert!(run_args(args).is_err());
+    }
 }
@@ -1,14 +1,26 @@
 use std::error::Error;
+use std::io::Write;
 
-use clap::Parser;
-use uuid::Uuid;
-use mac_address::get_mac_address;
+use chrono::{DateTime, TimeZone, Utc};
+use clap::{Parser, Subcommand, ValueEnum};
+use enum_dispatch::enum_dispatch;
+use indoc::indoc;
+use rand::{Rng, RngCore};
+use serde_json::{json, Value};
+use uuid::{Builder, Uuid, Variant};
+use crate::cli::utils::{base58_utils, base64_utils, field_utils::format_fields, hex_utils, radix_utils, size_utils};
 use super::super::{Runnable, BaseArgs};
+use super::timestamp::{self, format_timestamp, parse_timestamp};
 
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct UuidArgs {
-    #[arg(default_value="1")]
-    count: u32,
+    #[arg(
+        default_value = "1",
+        long_help = "Number of uuids to generate. Accepts suffixes like 10k or 10M",
+        value_parser = size_utils::parse_size
+    )]
+    count: u64,
 
     #[arg(short, long, default_value="false")]
     upper: bool,
@@ -18,27 +30,276 @@ pub struct UuidArgs {
 
     #[arg(short, long, default_value="false")]
     no_hyphens: bool,
+
+    #[arg(
+        long,
+        help = "Namespace for name-based (v3 and v5) uuids",
+        long_help = indoc!{"
+            Namespace for name-based (v3 and v5) uuids. Can be one of
+            the standard namespaces (dns, url, oid, x500) or any uuid.
+        "}
+    )]
+    namespace: Option<String>,
+
+    #[arg(
+        long,
+        help = "Name for name-based (v3 and v5) uuids",
+        long_help = indoc!{"
+            Name for name-based (v3 and v5) uuids. If omitted, names are
+            read from stdin, one per line, and a uuid is generated for each
+            of them. The count is ignored for name-based uuids.
+        "}
+    )]
+    name: Option<String>,
+
+    #[arg(
+        long,
+        help = "Embedded timestamp for time-based (v1, v6 and v7) uuids",
+        long_help = indoc!{"
+            Embedded timestamp for time-based (v1, v6 and v7) uuids.
+            Accepts any timestamp the timestamp command can parse. If
+            omitted, will default to the current time.
+        "}
+    )]
+    time: Option<String>,
+
+    #[arg(
+        long,
+        help = "Custom data for v8 uuids, as 32 hex characters",
+        long_help = indoc!{"
+            Custom data for v8 uuids, as 32 hex characters. The version
+            and variant bits will be overwritten. If omitted, the data is
+            read from stdin, one uuid per line.
+        "}
+    )]
+    bytes: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "NODE",
+        help = "Node for time-based (v1 and v6) uuids, as a mac address or 12 hex characters",
+        long_help = indoc!{"
+            Node for time-based (v1 and v6) uuids, as a mac address or 12
+            hex characters. Use `random` for a random node with the multicast
+            bit set, so that it can never collide with a real mac address.
+            If omitted, v1 uuids use the system mac address, falling back to
+            a random node if there is none, and v6 uuids use a random node.
+        "}
+    )]
+    node: Option<String>,
+
+    #[arg(
+        long,
+        value_parser = clap::value_parser!(u16).range(0..=0x3FFF),
+        help = "Clock sequence for time-based (v1 and v6) uuids",
+        long_help = indoc!{"
+            Clock sequence for time-based (v1 and v6) uuids, between 0 and
+            16383. If omitted, a random clock sequence is used.
+        "}
+    )]
+    clock_seq: Option<u16>,
+
+    #[command(subcommand)]
+    command: Option<UuidCommand>,
 }
 
-#[derive(Debug)]
-enum UuidError {
-    InvalidVersion,
-    MacAddressError(mac_address::MacAddressError),
-    MacAddressNotFound
+#[derive(Subcommand, Debug, Clone)]
+#[enum_dispatch(Runnable)]
+enum UuidCommand {
+    Inspect(InspectArgs),
+    Convert(ConvertArgs),
 }
 
-impl From<mac_address::MacAddressError> for UuidError {
-    fn from(err: mac_address::MacAddressError) -> Self {
-        UuidError::MacAddressError(err)
+#[derive(Parser, Debug, Clone)]
+#[command(
+    about = "Inspect an existing uuid",
+    long_about = indoc!{"
+        Parse a uuid and report its version and variant, as well as the
+        timestamp, clock sequence and node embedded in time-based uuids.
+    "},
+    after_long_help = indoc!{r#"
+        Examples:
+            $ medea uuid inspect 13814000-1dd2-11b2-85b2-76a05a5ca4e5
+            uuid:           13814000-1dd2-11b2-85b2-76a05a5ca4e5
+            nil:            false
+            max:            false
+            version:        1 (gregorian time-based)
+            variant:        rfc 9562
+            timestamp:      1970-01-01T00:00:00+00:00
+            unix timestamp: 0
+            clock sequence: 1458
+            node:           76:a0:5a:5c:a4:e5 (mac address)
+    "#}
+)]
+pub struct InspectArgs {
+    #[arg(help = "Uuid to inspect, in hyphenated, simple, braced or urn form")]
+    uuid: String,
+
+    #[arg(short = 'z', long, help = "Timezone of the embedded timestamp")]
+    timezone: Option<String>,
+
+    #[arg(long, default_value = "false", help = "Output as json")]
+    json: bool,
+}
+
+#[derive(Parser, Debug, Clone)]
+#[command(
+    about = "Convert a uuid between formats",
+    long_about = indoc!{"
+        Convert a uuid from one format to another. The input format is
+        detected automatically unless --from is given. The 22 character
+        b64, b58 and short forms often fit more than one alphabet, in which
+        case the conversion fails and --from has to be supplied. Input
+        in the guid format (mixed-endian hex) is never detected, since it
+        looks the same as the simple format.
+    "},
+    after_long_help = indoc!{r#"
+        Formats:
+            hyphenated  6ba7b810-9dad-11d1-80b4-00c04fd430c8
+            simple      6ba7b8109dad11d180b400c04fd430c8
+            braced      {6ba7b810-9dad-11d1-80b4-00c04fd430c8}
+            urn         urn:uuid:6ba7b810-9dad-11d1-80b4-00c04fd430c8
+            b64         a6e4EJ2tEdGAtADAT9QwyA (url-safe, unpadded)
+            b58         EJ34kCVxxF9jHMKD4EgrAK
+            short       MAnkyno2VCnFzuVMWtxBda (python shortuuid)
+            int         143098242404177361603877621312831893704
+            guid        10b8a76bad9dd11180b400c04fd430c8 (.NET Guid.ToByteArray order)
+            c           { 0x6b, 0xa7, 0xb8, 0x10, ... }
+            rust        [0x6b, 0xa7, 0xb8, 0x10, ...]
+
+        Examples:
+            # convert a sql server literal to a short uuid
+            $ medea uuid convert '{6ba7b810-9dad-11d1-80b4-00c04fd430c8}' -t short
+            MAnkyno2VCnFzuVMWtxBda
+
+            # get the .NET byte order of a guid
+            $ medea uuid convert 6ba7b810-9dad-11d1-80b4-00c04fd430c8 -t guid
+            10b8a76bad9dd11180b400c04fd430c8
+    "#}
+)]
+pub struct ConvertArgs {
+    #[arg(help = "Uuid to convert. If omitted, uuids are read from stdin, one per line")]
+    input: Option<String>,
+
+    #[arg(short, long, help = "Input format", value_enum, value_name = "FORMAT")]
+    from: Option<ConvertFormat>,
+
+    #[arg(short, long, help = "Output format", value_enum, value_name = "FORMAT", default_value = "hyphenated")]
+    to: ConvertFormat,
+
+    #[arg(short, long, help = "Use upper case characters for hex output", default_value = "false")]
+    upper: bool,
+}
+
+#[derive(ValueEnum, Debug, Clone, PartialEq)]
+pub enum ConvertFormat {
+    Hyphenated,
+    Simple,
+    Braced,
+    Urn,
+    B64,
+    B58,
+    Short,
+    Int,
+    Guid,
+    C,
+    Rust,
+}
+
+// gregorian ticks (100ns intervals since 1582-10-15) at the unix epoch
+const GREGORIAN_UNIX_OFFSET: i64 = 0x01B2_1DD2_1381_4000;
+const V7_RANDOM_BITS: u32 = 74;
+
+// state shared by the uuids in a single batch, used to keep time-based
+// uuids strictly increasing when they fall in the same clock tick
+#[derive(Default)]
+struct BatchState {
+    last_ticks: Option<u64>,
+    last_v7: Option<(u64, u128)>,
+    clock_seq: Option<u16>,
+    random_node: Option<[u8; 6]>,
+}
+
+impl BatchState {
+    fn next_ticks(&mut self, time: &DateTime<Utc>) -> Result<u64, UuidError> {
+        let ticks = time.timestamp()
+            .checked_mul(10_000_000)
+            .and_then(|t| t.checked_add(i64::from(time.timestamp_subsec_nanos() / 100)))
+            .and_then(|t| t.checked_add(GREGORIAN_UNIX_OFFSET))
+            .and_then(|t| u64::try_from(t).ok())
+            .ok_or(UuidError::InvalidTime(String::from("time is out of range for a gregorian timestamp")))?;
+
+        let ticks = match self.last_ticks {
+            Some(last) if ticks <= last => last + 1,
+            _ => ticks,
+        };
+        self.last_ticks = Some(ticks);
+        Ok(ticks)
+    }
+
+    fn clock_seq(&mut self, fixed: Option<u16>, rng: &mut dyn RngCore) -> u16 {
+        if let Some(clock_seq) = fixed {
+            return clock_seq;
+        }
+        *self.clock_seq.get_or_insert_with(|| rng.gen::<u16>() & 0x3FFF)
+    }
+
+    fn random_node(&mut self, rng: &mut dyn RngCore) -> [u8; 6] {
+        *self.random_node.get_or_insert_with(|| {
+            let mut node: [u8; 6] = rng.gen();
+            // set the multicast bit, so the node can't collide with a real mac address
+            node[0] |= 0x01;
+            node
+        })
+    }
+
+    fn next_v7(&mut self, time: &DateTime<Utc>, rng: &mut dyn RngCore) -> Result<Uuid, UuidError> {
+        let millis = u64::try_from(time.timestamp_millis())
+            .ok()
+            .filter(|m| m >> 48 == 0)
+            .ok_or(UuidError::InvalidTime(String::from("time is out of range for a v7 uuid")))?;
+
+        // leave the top bit clear so the counter has room to grow
+        let mut fresh_random = || rng.gen::<u128>() & ((1u128 << (V7_RANDOM_BITS - 1)) - 1);
+        let (millis, random) = match self.last_v7 {
+            Some((last_millis, last_random)) if millis <= last_millis => {
+                match last_random + 1 {
+                    r if r >> V7_RANDOM_BITS == 0 => (last_millis, r),
+                    _ => (last_millis + 1, fresh_random()),
+                }
+            },
+            _ => (millis, fresh_random()),
+        };
+        self.last_v7 = Some((millis, random));
+
+        let value = (u128::from(millis) << 80)
+            | (0x7u128 << 76)
+            | ((random >> 62) << 64)
+            | (0b10u128 << 62)
+            | (random & ((1u128 << 62) - 1));
+        Ok(Uuid::from_u128(value))
     }
 }
 
+#[derive(Debug)]
+enum UuidError {
+    InvalidVersion,
+    InvalidNode(String),
+    MissingNamespace,
+    InvalidNamespace(String),
+    InvalidTime(String),
+    InvalidBytes(String),
+}
+
 impl std::fmt::Display for UuidError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            UuidError::InvalidVersion => write!(f, "Invalid UUID version. Supported versions are '1' and '4'"),
-            UuidError::MacAddressError(_) => write!(f, "Error retrieving system MAC address"),
-            UuidError::MacAddressNotFound => write!(f, "Error retrieving system MAC address"),
+            UuidError::InvalidVersion => write!(f, "Invalid UUID version. Supported versions are '1', '3', '4', '5', '6', '7' and '8'"),
+            UuidError::InvalidNode(s) => write!(f, "Invalid node: {:?}. Expected a MAC address, 12 hex characters or random", s),
+            UuidError::MissingNamespace => write!(f, "A namespace is required for name-based UUIDs"),
+            UuidError::InvalidNamespace(s) => write!(f, "Invalid namespace: {:?}. Expected dns, url, oid, x500 or a UUID", s),
+            UuidError::InvalidTime(s) => write!(f, "Invalid time: {}", s),
+            UuidError::InvalidBytes(s) => write!(f, "Invalid bytes: {:?}. Expected 32 hex characters", s),
         }
     }
 }
@@ -46,37 +307,681 @@ impl std::fmt::Display for UuidError {
 impl std::error::Error for UuidError {}
 
 
+#[cfg(feature = "mac-address")]
+fn system_node() -> Option<[u8; 6]> {
+    mac_address::get_mac_address().ok().flatten().map(|ma| ma.bytes())
+}
+
+#[cfg(not(feature = "mac-address"))]
+fn system_node() -> Option<[u8; 6]> {
+    None
+}
+
 impl UuidArgs {
-    fn get_uuid(&self) -> Result<Uuid, UuidError> {
+    fn get_time(&self, base_args: &BaseArgs) -> Result<DateTime<Utc>, UuidError> {
+        match &self.time {
+            Some(t) => parse_timestamp(t),
+            None => base_args.now(),
+        }.map_err(|e| UuidError::InvalidTime(e.to_string()))
+    }
+
+    fn get_uuid(&self, base_args: &BaseArgs, state: &mut BatchState, rng: &mut dyn RngCore) -> Result<Uuid, UuidError> {
         match self.version.as_str() {
             "1" => {
-                let ma = get_mac_address()?.ok_or(UuidError::MacAddressNotFound)?;
-                return Ok(Uuid::now_v1(&ma.bytes()));
+                let node = match self.get_node(state, rng)? {
+                    Some(node) => node,
+                    None => system_node().unwrap_or_else(|| state.random_node(rng)),
+                };
+                let ticks = state.next_ticks(&self.get_time(base_args)?)?;
+                return Ok(Builder::from_gregorian_timestamp(ticks, state.clock_seq(self.clock_seq, rng), &node).into_uuid());
             }
             "4" => {
-                return Ok(Uuid::new_v4());
+                return Ok(Builder::from_random_bytes(rng.gen()).into_uuid());
             }
+            "6" => {
+                let ticks = state.next_ticks(&self.get_time(base_args)?)?;
+                let node = match self.get_node(state, rng)? {
+                    Some(node) => node,
+                    None => state.random_node(rng),
+                };
+                Ok(Builder::from_sorted_gregorian_timestamp(ticks, state.clock_seq(self.clock_seq, rng), &node).into_uuid())
+            }
+            "7" => state.next_v7(&self.get_time(base_args)?, rng),
             _ => Err(UuidError::InvalidVersion)
         }
     }
 
-    fn get_uuid_string(&self) -> Result<String, Box<dyn Error>>{
-        let uuid = self.get_uuid()?;
-        let mut t = if self.no_hyphens { uuid.simple().to_string() } else { uuid.to_string() };
-        if self.upper { t = t.to_uppercase(); }
-        return Ok(t);
+    fn get_node(&self, state: &mut BatchState, rng: &mut dyn RngCore) -> Result<Option<[u8; 6]>, UuidError> {
+        let node = match &self.node {
+            Some(node) => node,
+            None => return Ok(None),
+        };
+        if node.eq_ignore_ascii_case("random") {
+            return Ok(Some(state.random_node(rng)));
+        }
+
+        let digits: String = node.trim().chars().filter(|c| *c != ':' && *c != '-').collect();
+        if digits.len() != 12 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(UuidError::InvalidNode(node.clone()));
+        }
+        let bytes: [u8; 6] = hex_utils::decode(digits).try_into().map_err(|_| UuidError::InvalidNode(node.clone()))?;
+        Ok(Some(bytes))
+    }
+
+    fn get_custom_uuid(hex: &str) -> Result<Uuid, UuidError> {
+        let digits: String = hex.trim().chars().filter(|c| *c != '-').collect();
+        if digits.len() != 32 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(UuidError::InvalidBytes(hex.to_string()));
+        }
+
+        let bytes: [u8; 16] = hex_utils::decode(digits).try_into().map_err(|_| UuidError::InvalidBytes(hex.to_string()))?;
+        Ok(Builder::from_custom_bytes(bytes).into_uuid())
+    }
+
+    fn get_namespace(&self) -> Result<Uuid, UuidError> {
+        let namespace = self.namespace.as_ref().ok_or(UuidError::MissingNamespace)?;
+        match namespace.to_lowercase().as_str() {
+            "dns" => Ok(Uuid::NAMESPACE_DNS),
+            "url" => Ok(Uuid::NAMESPACE_URL),
+            "oid" => Ok(Uuid::NAMESPACE_OID),
+            "x500" => Ok(Uuid::NAMESPACE_X500),
+            _ => Uuid::parse_str(namespace).map_err(|_| UuidError::InvalidNamespace(namespace.clone())),
+        }
+    }
+
+    fn get_name_based_uuid(&self, namespace: &Uuid, name: &str) -> Result<Uuid, UuidError> {
+        match self.version.as_str() {
+            "3" => Ok(Uuid::new_v3(namespace, name.as_bytes())),
+            "5" => Ok(Uuid::new_v5(namespace, name.as_bytes())),
+            _ => Err(UuidError::InvalidVersion)
+        }
+    }
+
+    fn encode_uuid<'a>(&self, uuid: &Uuid, buffer: &'a mut [u8]) -> &'a str {
+        match (self.no_hyphens, self.upper) {
+            (false, false) => uuid.hyphenated().encode_lower(buffer),
+            (false, true) => uuid.hyphenated().encode_upper(buffer),
+            (true, false) => uuid.simple().encode_lower(buffer),
+            (true, true) => uuid.simple().encode_upper(buffer),
+        }
+    }
+
+    fn format_uuid(&self, uuid: &Uuid) -> String {
+        self.encode_uuid(uuid, &mut Uuid::encode_buffer()).to_string()
+    }
+
+    fn get_custom_uuid_strings(&self, get_input: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        let inputs = match &self.bytes {
+            Some(bytes) => vec![bytes.clone()],
+            None => get_input().lines().map(|l| l.to_string()).collect(),
+        };
+
+        let uuids = inputs
+            .iter()
+            .map(|hex| Ok(self.format_uuid(&Self::get_custom_uuid(hex)?)))
+            .collect::<Result<Vec<String>, UuidError>>()?;
+        Ok(uuids.join("\n"))
+    }
+
+    fn get_name_based_uuid_strings(&self, get_input: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        let namespace = self.get_namespace()?;
+        let names = match &self.name {
+            Some(name) => vec![name.clone()],
+            None => get_input().lines().map(|l| l.to_string()).collect(),
+        };
+
+        let uuids = names
+            .iter()
+            .map(|name| Ok(self.format_uuid(&self.get_name_based_uuid(&namespace, name)?)))
+            .collect::<Result<Vec<String>, UuidError>>()?;
+        Ok(uuids.join("\n"))
     }
 
 }
 
+impl InspectArgs {
+    fn version_name(version: usize) -> &'static str {
+        match version {
+            1 => "gregorian time-based",
+            2 => "dce security",
+            3 => "name-based, md5",
+            4 => "random",
+            5 => "name-based, sha-1",
+            6 => "reordered gregorian time-based",
+            7 => "unix epoch time-based",
+            8 => "custom",
+            _ => "unknown",
+        }
+    }
+
+    fn variant_name(variant: Variant) -> &'static str {
+        match variant {
+            Variant::NCS => "ncs",
+            Variant::RFC4122 => "rfc 9562",
+            Variant::Microsoft => "microsoft",
+            _ => "future",
+        }
+    }
+
+    fn get_timestamp(uuid: &Uuid) -> Option<DateTime<Utc>> {
+        let bytes = uuid.as_bytes();
+        let high = u64::from(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]));
+        let mid = u64::from(u16::from_be_bytes([bytes[4], bytes[5]]));
+        let low = u64::from(u16::from_be_bytes([bytes[6], bytes[7]]) & 0x0FFF);
+
+        let ticks = match uuid.get_version_num() {
+            1 => (low << 48) | (mid << 32) | high,
+            6 => (high << 28) | (mid << 12) | low,
+            7 => {
+                let millis = (high << 16) | mid;
+                return Utc.timestamp_millis_opt(millis as i64).single();
+            },
+            _ => return None,
+        };
+
+        let unix_ticks = ticks as i64 - GREGORIAN_UNIX_OFFSET;
+        Utc.timestamp_opt(unix_ticks.div_euclid(10_000_000), (unix_ticks.rem_euclid(10_000_000) * 100) as u32).single()
+    }
+
+    fn inspect(&self) -> Result<Vec<(&'static str, Value)>, Box<dyn Error>> {
+        let uuid = ConvertFormat::parse(&self.uuid, None)?;
+        let version = uuid.get_version_num();
+        let bytes = uuid.as_bytes();
+
+        let mut fields = vec![
+            ("uuid", json!(uuid.to_string())),
+            ("nil", json!(uuid.is_nil())),
+            ("max", json!(uuid.is_max())),
+        ];
+        if uuid.is_nil() || uuid.is_max() {
+            return Ok(fields);
+        }
+
+        fields.push(("version", json!(version)));
+        fields.push(("version name", json!(Self::version_name(version))));
+        fields.push(("variant", json!(Self::variant_name(uuid.get_variant()))));
+
+        if let Some(ts) = Self::get_timestamp(&uuid) {
+            fields.push(("timestamp", json!(format_timestamp(&ts, &timestamp::Format::Iso, &self.timezone)?)));
+            fields.push(("unix timestamp", json!(ts.timestamp())));
+        }
+
+        if version == 1 || version == 6 {
+            let clock_seq = u16::from_be_bytes([bytes[8], bytes[9]]) & 0x3FFF;
+            let node = bytes[10..].iter().map(|b| format!("{:02x}", b)).collect::<Vec<String>>().join(":");
+            fields.push(("clock sequence", json!(clock_seq)));
+            fields.push(("node", json!(node)));
+            // the multicast bit is never set for a real mac address
+            fields.push(("node type", json!(if bytes[10] & 0x01 == 1 { "random" } else { "mac address" })));
+        }
+
+        Ok(fields)
+    }
+}
+
+impl Runnable for InspectArgs {
+    fn run(&self, _: &BaseArgs, _: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        format_fields(self.inspect()?, self.json)
+    }
+}
+
+impl ConvertFormat {
+    fn is_base64_url(input: &str) -> bool {
+        input.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    }
+
+    fn detect(input: &str) -> Result<ConvertFormat, Box<dyn Error>> {
+        let format = if input.to_lowercase().starts_with("urn:uuid:") {
+            ConvertFormat::Urn
+        } else if input.contains(',') {
+            ConvertFormat::C
+        } else if input.starts_with('{') {
+            ConvertFormat::Braced
+        } else if input.len() == 36 {
+            ConvertFormat::Hyphenated
+        } else if input.len() == 32 && input.chars().all(|c| c.is_ascii_hexdigit()) {
+            ConvertFormat::Simple
+        } else if input.chars().all(|c| c.is_ascii_digit()) {
+            ConvertFormat::Int
+        } else if input.len() == 24 && input.ends_with("==") {
+            ConvertFormat::B64
+        } else if input.len() == 22 {
+            // a string can fit several of these alphabets at once, so only
+            // pick a format if it is the one that gives the same string back
+            let formats: Vec<ConvertFormat> = [ConvertFormat::B58, ConvertFormat::Short, ConvertFormat::B64]
+                .into_iter()
+                .filter(|f| Self::parse(input, Some(f)).is_ok_and(|uuid| f.format(&uuid, false) == input))
+                .collect();
+            match formats.as_slice() {
+                [format] => format.clone(),
+                [] => return Err(format!("unable to detect uuid format of {:?}", input).into()),
+                formats => {
+                    let names: Vec<String> = formats
+                        .iter()
+                        .filter_map(|f| f.to_possible_value().map(|v| v.get_name().to_string()))
+                        .collect();
+                    return Err(format!("{:?} could be any of {}, use --from to pick one", input, names.join(", ")).into());
+                },
+            }
+        } else {
+            return Err(format!("unable to detect uuid format of {:?}", input).into());
+        };
+        Ok(format)
+    }
+
+    fn parse_byte_array(input: &str) -> Result<[u8; 16], Box<dyn Error>> {
+        let inner = input.trim_start_matches(['{', '[']).trim_end_matches([';', '}', ']']);
+        let bytes = inner
+            .split(',')
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(|s| {
+                let parsed = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+                    Some(hex) => u8::from_str_radix(hex, 16),
+                    None => s.trim_end_matches("u8").parse::<u8>(),
+                };
+                parsed.map_err(|_| format!("invalid byte: {:?}", s))
+            })
+            .collect::<Result<Vec<u8>, String>>()?;
+        let count = bytes.len();
+        Ok(bytes.try_into().map_err(|_| format!("expected 16 bytes, found {}", count))?)
+    }
+
+    pub fn parse(input: &str, from: Option<&ConvertFormat>) -> Result<Uuid, Box<dyn Error>> {
+        let input = input.trim();
+        let format = match from {
+            Some(f) => f.clone(),
+            None => Self::detect(input)?,
+        };
+
+        let invalid = |e: &dyn std::fmt::Display| format!("unable to parse {:?} as {:?}: {}", input, format, e);
+        let uuid = match format {
+            ConvertFormat::Hyphenated | ConvertFormat::Simple | ConvertFormat::Braced | ConvertFormat::Urn => {
+                Uuid::parse_str(input).map_err(|e| invalid(&e))?
+            },
+            ConvertFormat::B64 => {
+                let bytes = match Self::is_base64_url(input) {
+                    true => base64_utils::decode_url(input),
+                    false if input.chars().all(|c| c.is_ascii_alphanumeric() || "+/=".contains(c)) => base64_utils::decode(input),
+                    false => return Err(invalid(&"unexpected character").into()),
+                };
+                Uuid::from_slice(&bytes).map_err(|e| invalid(&e))?
+            },
+            ConvertFormat::B58 => Uuid::from_u128(radix_utils::decode_u128(input, base58_utils::BASE58_ALPHABET).map_err(|e| invalid(&e))?),
+            ConvertFormat::Short => Uuid::from_u128(radix_utils::decode_u128(input, base58_utils::BASE57_ALPHABET).map_err(|e| invalid(&e))?),
+            ConvertFormat::Int => Uuid::from_u128(input.parse::<u128>().map_err(|e| invalid(&e))?),
+            ConvertFormat::Guid => {
+                let simple = Uuid::try_parse(input).map_err(|e| invalid(&e))?;
+                Uuid::from_bytes_le(*simple.as_bytes())
+            },
+            ConvertFormat::C | ConvertFormat::Rust => Uuid::from_bytes(Self::parse_byte_array(input).map_err(|e| invalid(&e))?),
+        };
+        Ok(uuid)
+    }
+
+    fn format(&self, uuid: &Uuid, upper: bool) -> String {
+        let byte_list = |bytes: &[u8]| bytes.iter().map(|b| match upper {
+            true => format!("0x{:02X}", b),
+            false => format!("0x{:02x}", b),
+        }).collect::<Vec<String>>().join(", ");
+
+        let output = match self {
+            ConvertFormat::Hyphenated => uuid.hyphenated().to_string(),
+            ConvertFormat::Simple => uuid.simple().to_string(),
+            ConvertFormat::Braced => uuid.braced().to_string(),
+            ConvertFormat::Urn => uuid.urn().to_string(),
+            ConvertFormat::B64 => base64_utils::encode_url(uuid.as_bytes()),
+            ConvertFormat::B58 => radix_utils::encode_u128(uuid.as_u128(), base58_utils::BASE58_ALPHABET, 22),
+            ConvertFormat::Short => radix_utils::encode_u128(uuid.as_u128(), base58_utils::BASE57_ALPHABET, 22),
+            ConvertFormat::Int => uuid.as_u128().to_string(),
+            ConvertFormat::Guid => hex_utils::encode(&uuid.to_bytes_le(), false),
+            ConvertFormat::C => return format!("{{ {} }}", byte_list(uuid.as_bytes())),
+            ConvertFormat::Rust => return format!("[{}]", byte_list(uuid.as_bytes())),
+        };
+
+        match self {
+            ConvertFormat::Hyphenated | ConvertFormat::Simple | ConvertFormat::Braced | ConvertFormat::Urn | ConvertFormat::Guid if upper => {
+                output.to_uppercase().replace("URN:UUID:", "urn:uuid:")
+            },
+            _ => output,
+        }
+    }
+}
+
+impl Runnable for ConvertArgs {
+    fn run(&self, _: &BaseArgs, get_input: impl Fn() -> String) -> Result<String, Box<dyn Error>> {
+        let inputs = match &self.input {
+            Some(input) => vec![input.clone()],
+            None => get_input().lines().map(|l| l.to_string()).collect(),
+        };
+
+        let outputs = inputs
+            .iter()
+            .map(|input| Ok(self.to.format(&ConvertFormat::parse(input, self.from.as_ref())?, self.upper)))
+            .collect::<Result<Vec<String>, Box<dyn Error>>>()?;
+        Ok(outputs.join("\n"))
+    }
+}
+
 impl Runnable for UuidArgs {
-    fn run(&self, _: &BaseArgs, _:impl Fn() -> String) -> Result<String,Box<dyn Error>> {
-        if self.count == 0 { return Ok(String::new()); }
+    fn run(&self, base_args: &BaseArgs, get_input: impl Fn() -> String) -> Result<String,Box<dyn Error>> {
+        let mut buffer = Vec::new();
+        self.run_to(base_args, get_input, &mut buffer)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+
+    fn run_to(&self, base_args: &BaseArgs, get_input: impl Fn() -> String, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+        if let Some(command) = &self.command {
+            return command.run_to(base_args, get_input, out);
+        }
+
+        if self.version == "3" || self.version == "5" {
+            out.write_all(self.get_name_based_uuid_strings(get_input)?.as_bytes())?;
+            return Ok(());
+        }
+        if self.version == "8" {
+            out.write_all(self.get_custom_uuid_strings(get_input)?.as_bytes())?;
+            return Ok(());
+        }
 
-        let mut s = self.get_uuid_string()?;
-        for _ in 0..(self.count - 1) {
-            s += &format!("\n{}", &self.get_uuid_string()?);
+        let mut state = BatchState::default();
+        let mut rng = base_args.rng();
+        let mut buffer = Uuid::encode_buffer();
+        for i in 0..self.count {
+            if i > 0 {
+                out.write_all(b"\n")?;
+            }
+            let uuid = self.get_uuid(base_args, &mut state, &mut *rng)?;
+            out.write_all(self.encode_uuid(&uuid, &mut buffer).as_bytes())?;
         }
-        return Ok(s);
+        Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use rstest::rstest;
+    use uuid::Uuid;
+
+    use crate::cli::{
+        args::{BaseArgs, Runnable},
+        ArgsEnum,
+    };
+
+    use super::{BatchState, ConvertArgs, ConvertFormat, InspectArgs, UuidArgs, UuidCommand};
+
+    fn args(version: &str) -> UuidArgs {
+        UuidArgs {
+            count: 1,
+            upper: false,
+            version: String::from(version),
+            no_hyphens: false,
+            namespace: None,
+            name: None,
+            time: None,
+            bytes: None,
+            node: None,
+            clock_seq: None,
+            command: None,
+        }
+    }
+
+    fn run(sut: UuidArgs, input: &str) -> Result<String, Box<dyn std::error::Error>> {
+        let base_args = BaseArgs::fixture(ArgsEnum::Uuid(sut.clone()));
+        let input = String::from(input);
+        sut.run(&base_args, move || input.clone())
+    }
+
+    #[rstest(version, time, expected_result,
+        case("4", None, "0d995e4b-e0ca-418e-b040-34eae7d6f3be\n45989bb1-678f-4b78-8291-b490beb6432a"),
+        case("7", Some("0"), "00000000-0000-757b-b274-d49952e0920d\n00000000-0000-757b-b274-d49952e0920e"),
+    )]
+    fn will_generate_same_uuids_for_same_seed(version: &str, time: Option<&str>, expected_result: &str) {
+        let sut = UuidArgs {
+            count: 2,
+            time: time.map(String::from),
+            ..args(version)
+        };
+        assert_eq!(run(sut, "").unwrap(), expected_result);
+    }
+
+    #[rstest(version, namespace, name, expected_result,
+        case("3", "dns", "python.org", "6fa459ea-ee8a-3ca4-894e-db77e160355e"),
+        case("5", "dns", "python.org", "886313e1-3b8a-5372-9b90-0c9aee199e5d"),
+        case("5", "6ba7b810-9dad-11d1-80b4-00c04fd430c8", "python.org", "886313e1-3b8a-5372-9b90-0c9aee199e5d"),
+    )]
+    fn will_generate_name_based_uuid(version: &str, namespace: &str, name: &str, expected_result: &str) {
+        let sut = UuidArgs {
+            namespace: Some(String::from(namespace)),
+            name: Some(String::from(name)),
+            ..args(version)
+        };
+        assert_eq!(run(sut, "").unwrap(), expected_result);
+    }
+
+    #[test]
+    fn will_read_names_from_input() {
+        let sut = UuidArgs {
+            namespace: Some(String::from("dns")),
+            ..args("5")
+        };
+        let result = run(sut, "python.org\npython.org\n").unwrap();
+        assert_eq!(result, "886313e1-3b8a-5372-9b90-0c9aee199e5d\n886313e1-3b8a-5372-9b90-0c9aee199e5d");
+    }
+
+    #[test]
+    fn will_reject_missing_namespace() {
+        let sut = UuidArgs {
+            name: Some(String::from("python.org")),
+            ..args("5")
+        };
+        assert!(run(sut, "").is_err());
+    }
+
+    #[rstest(version, case("6"), case("7"))]
+    fn will_generate_increasing_uuids_with_pinned_time(version: &str) {
+        let sut = UuidArgs {
+            count: 1000,
+            time: Some(String::from("2023-08-20T15:30:00Z")),
+            ..args(version)
+        };
+        let result = run(sut, "").unwrap();
+        let uuids: Vec<uuid::Uuid> = result.lines().map(|l| uuid::Uuid::parse_str(l).unwrap()).collect();
+
+        assert_eq!(uuids.len(), 1000);
+        assert!(uuids.windows(2).all(|w| w[0] < w[1]));
+        assert!(uuids.iter().all(|u| u.get_version_num().to_string() == version));
+    }
+
+    #[test]
+    fn will_embed_pinned_time_in_v7_uuid() {
+        let sut = UuidArgs {
+            time: Some(String::from("1692545400")),
+            ..args("7")
+        };
+        let result = run(sut, "").unwrap();
+        // 1692545400000 milliseconds
+        assert!(result.starts_with("018a1391-3cc0-7"));
+    }
+
+    #[rstest(version, expected_result,
+        case("1", "13814000-1dd2-11b2-85b2-76a05a5ca4e5"),
+        case("6", "1b21dd21-3814-6000-85b2-76a05a5ca4e5"),
+    )]
+    fn will_generate_time_based_uuid_with_node_and_clock_seq(version: &str, expected_result: &str) {
+        let sut = UuidArgs {
+            time: Some(String::from("0")),
+            node: Some(String::from("76:A0:5A:5C:A4:E5")),
+            clock_seq: Some(1458),
+            ..args(version)
+        };
+        assert_eq!(run(sut, "").unwrap(), expected_result);
+    }
+
+    #[test]
+    fn will_generate_v1_uuid_with_random_multicast_node() {
+        let sut = UuidArgs {
+            count: 10,
+            node: Some(String::from("random")),
+            ..args("1")
+        };
+        let result = run(sut, "").unwrap();
+        let uuids: Vec<uuid::Uuid> = result.lines().map(|l| uuid::Uuid::parse_str(l).unwrap()).collect();
+        assert!(uuids.iter().all(|u| u.as_bytes()[10] & 0x01 == 1 && u.as_bytes()[10..] == uuids[0].as_bytes()[10..]));
+    }
+
+    #[test]
+    fn will_reject_invalid_node() {
+        let sut = UuidArgs {
+            node: Some(String::from("76:a0:5a:5c:a4")),
+            ..args("1")
+        };
+        assert!(run(sut, "").is_err());
+    }
+
+    #[test]
+    fn will_generate_v8_uuid_from_bytes() {
+        let sut = UuidArgs {
+            bytes: Some(String::from("ffffffffffffffffffffffffffffffff")),
+            ..args("8")
+        };
+        assert_eq!(run(sut, "").unwrap(), "ffffffff-ffff-8fff-bfff-ffffffffffff");
+    }
+
+    #[rstest(uuid, expected_result,
+        case("13814000-1dd2-11b2-85b2-76a05a5ca4e5", "1970-01-01T00:00:00+00:00"),
+        case("{1ddfa267-0d9b-6500-b538-25ebd499183a}", "2009-02-13T23:31:30+00:00"),
+        case("urn:uuid:018a1391-3cc0-7000-8000-000000000000", "2023-08-20T15:30:00+00:00"),
+    )]
+    fn will_inspect_embedded_timestamp(uuid: &str, expected_result: &str) {
+        let sut = UuidArgs {
+            command: Some(UuidCommand::Inspect(InspectArgs {
+                uuid: String::from(uuid),
+                timezone: None,
+                json: true,
+            })),
+            ..args("4")
+        };
+        let result: serde_json::Value = serde_json::from_str(&run(sut, "").unwrap()).unwrap();
+        assert_eq!(result["timestamp"], expected_result);
+    }
+
+    #[test]
+    fn will_inspect_v1_node() {
+        let sut = UuidArgs {
+            command: Some(UuidCommand::Inspect(InspectArgs {
+                uuid: String::from("13814000-1dd2-11b2-85b2-76a05a5ca4e5"),
+                timezone: None,
+                json: false,
+            })),
+            ..args("4")
+        };
+        let result = run(sut, "").unwrap();
+        assert!(result.contains("version:        1 (gregorian time-based)"));
+        assert!(result.contains("clock sequence: 1458"));
+        assert!(result.contains("node:           76:a0:5a:5c:a4:e5 (mac address)"));
+    }
+
+    #[test]
+    fn will_inspect_max_uuid() {
+        let sut = UuidArgs {
+            command: Some(UuidCommand::Inspect(InspectArgs {
+                uuid: String::from("FFFFFFFF-FFFF-FFFF-FFFF-FFFFFFFFFFFF"),
+                timezone: None,
+                json: true,
+            })),
+            ..args("4")
+        };
+        let result: serde_json::Value = serde_json::from_str(&run(sut, "").unwrap()).unwrap();
+        assert_eq!(result["max"], true);
+        assert_eq!(result["nil"], false);
+    }
+
+    #[rstest(input, from, to, expected_result,
+        case("6ba7b810-9dad-11d1-80b4-00c04fd430c8", None, ConvertFormat::Braced, "{6ba7b810-9dad-11d1-80b4-00c04fd430c8}"),
+        case("{6ba7b810-9dad-11d1-80b4-00c04fd430c8}", None, ConvertFormat::Urn, "urn:uuid:6ba7b810-9dad-11d1-80b4-00c04fd430c8"),
+        case("urn:uuid:6ba7b810-9dad-11d1-80b4-00c04fd430c8", None, ConvertFormat::Simple, "6ba7b8109dad11d180b400c04fd430c8"),
+        case("6ba7b8109dad11d180b400c04fd430c8", None, ConvertFormat::B64, "a6e4EJ2tEdGAtADAT9QwyA"),
+        case("a6e4EJ2tEdGAtADAT9QwyA", Some(ConvertFormat::B64), ConvertFormat::B58, "EJ34kCVxxF9jHMKD4EgrAK"),
+        case("EJ34kCVxxF9jHMKD4EgrAK", Some(ConvertFormat::B58), ConvertFormat::Short, "MAnkyno2VCnFzuVMWtxBda"),
+        case("MAnkyno2VCnFzuVMWtxBda", Some(ConvertFormat::Short), ConvertFormat::Int, "143098242404177361603877621312831893704"),
+        case("143098242404177361603877621312831893704", None, ConvertFormat::Guid, "10b8a76bad9dd11180b400c04fd430c8"),
+        case("a6e4EJ2tEdGAtADAT9QwyA==", None, ConvertFormat::Hyphenated, "6ba7b810-9dad-11d1-80b4-00c04fd430c8"),
+        case("00000000-0000-0000-0000-0000000000ff", None, ConvertFormat::Rust, "[0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff]"),
+        case("{ 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff };", None, ConvertFormat::Short, "222222222222222222226V"),
+        case("[0u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 255]", None, ConvertFormat::Int, "255"),
+    )]
+    fn will_convert_uuid(input: &str, from: Option<ConvertFormat>, to: ConvertFormat, expected_result: &str) {
+        let sut = UuidArgs {
+            command: Some(UuidCommand::Convert(ConvertArgs {
+                input: Some(String::from(input)),
+                from,
+                to,
+                upper: false,
+            })),
+            ..args("4")
+        };
+        assert_eq!(run(sut, "").unwrap(), expected_result);
+    }
+
+    #[rstest(uuid, format,
+        case("2f4b9442-2f22-4df8-8ee0-27cab88520c2", ConvertFormat::B58),
+        case("f0e1d2c3-b4a5-4697-8879-6a5b4c3d2e1f", ConvertFormat::Short),
+        case("2f4b9442-2f22-4df8-8ee0-27cab88520c2", ConvertFormat::B64),
+    )]
+    fn will_detect_format_when_round_tripping(uuid: &str, format: ConvertFormat) {
+        let encoded = format.format(&Uuid::parse_str(uuid).unwrap(), false);
+        assert_eq!(ConvertFormat::parse(&encoded, None).unwrap().to_string(), uuid);
+    }
+
+    #[rstest(input,
+        case("Fg5Dw9UCZfZPTYsSmVVqAB"),
+        case("PA8iVhZ769wxGrcNWTyH2Q"),
+        case("dtSNAADaRfuDGCDLN6ietA"),
+    )]
+    fn will_reject_ambiguous_formats(input: &str) {
+        let error = ConvertFormat::parse(input, None).unwrap_err().to_string();
+        assert!(error.contains("--from"), "{}", error);
+    }
+
+    #[test]
+    fn will_convert_from_guid_byte_order() {
+        let sut = UuidArgs {
+            command: Some(UuidCommand::Convert(ConvertArgs {
+                input: None,
+                from: Some(ConvertFormat::Guid),
+                to: ConvertFormat::Hyphenated,
+                upper: true,
+            })),
+            ..args("4")
+        };
+        assert_eq!(run(sut, "10b8a76bad9dd11180b400c04fd430c8\n").unwrap(), "6BA7B810-9DAD-11D1-80B4-00C04FD430C8");
+    }
+
+    #[test]
+    #[ignore = "benchmark, run with `cargo test --release -- --ignored --nocapture`"]
+    fn bench_streamed_generation() {
+        let sut = UuidArgs { count: 1_000_000, ..args("4") };
+        let base_args = BaseArgs::try_parse_from(["medea", "uuid"]).unwrap();
+
+        // the previous approach, growing a single string with format!
+        let start = std::time::Instant::now();
+        {
+            let mut state = BatchState::default();
+            let mut rng = base_args.rng();
+            let mut s = sut.format_uuid(&sut.get_uuid(&base_args, &mut state, &mut *rng).unwrap());
+            for _ in 1..sut.count {
+                s += &format!("\n{}", &sut.format_uuid(&sut.get_uuid(&base_args, &mut state, &mut *rng).unwrap()));
+            }
+            assert_eq!(s.len() as u64, sut.count * 37 - 1);
+        }
+        let concatenated = start.elapsed();
+
+        let start = std::time::Instant::now();
+        sut.run_to(&base_args, String::new, &mut std::io::BufWriter::new(std::io::sink())).unwrap();
+        let streamed = start.elapsed();
+
+        println!("{} uuids: concatenated in {:?}, streamed in {:?}", sut.count, concatenated, streamed);
+    }
+}
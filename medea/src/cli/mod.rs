@@ -13,6 +13,13 @@ use commands::timestamp::TimeStampArgs;
 use commands::random::RandomArgs;
 use commands::base_convert::BaseConvertArgs;
 use commands::jwt::JwtArgs;
+use commands::crypt::CryptArgs;
+use commands::kdf::KdfArgs;
+use commands::otp::OtpArgs;
+use commands::id::IdArgs;
+use commands::password::PasswordArgs;
+use commands::fake::FakeArgs;
+use commands::gen::GenArgs;
 
 #[derive(Parser, Debug)]
 #[enum_dispatch(Runnable,)]
@@ -26,6 +33,14 @@ pub enum ArgsEnum {
     #[command(visible_alias="base")]
     BaseConvert(BaseConvertArgs),
     Jwt(JwtArgs),
+    Crypt(CryptArgs),
+    Kdf(KdfArgs),
+    Otp(OtpArgs),
+    Id(IdArgs),
+    #[command(visible_alias="pw")]
+    Password(PasswordArgs),
+    Fake(FakeArgs),
+    Gen(GenArgs),
 }
 
 pub use args::run;
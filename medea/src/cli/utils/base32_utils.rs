@@ -0,0 +1,101 @@
+use std::error::Error;
+
+const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+pub fn encode(bytes: &[u8], pad: bool) -> String {
+    let mut result = String::with_capacity(bytes.len().div_ceil(5) * 8);
+    let mut buffer = 0u16;
+    let mut buffer_length = 0;
+
+    for &byte in bytes {
+        buffer = (buffer << 8) | u16::from(byte);
+        buffer_length += 8;
+        while buffer_length >= 5 {
+            let index = (buffer >> (buffer_length - 5)) & 0x1F;
+            result.push(ALPHABET[index as usize] as char);
+            buffer_length -= 5;
+        }
+    }
+
+    // flush any leftover bits, padded with zeroes on the right
+    if buffer_length > 0 {
+        let index = (buffer << (5 - buffer_length)) & 0x1F;
+        result.push(ALPHABET[index as usize] as char);
+    }
+
+    if pad {
+        while !result.len().is_multiple_of(8) {
+            result.push('=');
+        }
+    }
+
+    result
+}
+
+pub fn decode(input: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut result = Vec::with_capacity(input.len() * 5 / 8);
+    let mut buffer = 0u16;
+    let mut buffer_length = 0;
+
+    for c in input.chars() {
+        // secrets are often shown in lower case or in space-delimited groups
+        if c == '=' || c == ' ' || c == '-' {
+            continue;
+        }
+
+        let value = match c.to_ascii_uppercase() {
+            u @ 'A'..='Z' => u as u8 - b'A',
+            d @ '2'..='7' => d as u8 - b'2' + 26,
+            _ => return Err(format!("invalid character in base32 string: {:?}", c).into()),
+        };
+
+        buffer = (buffer << 5) | u16::from(value);
+        buffer_length += 5;
+        if buffer_length >= 8 {
+            result.push((buffer >> (buffer_length - 8)) as u8);
+            buffer_length -= 8;
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    #[rstest(input, expected_result,
+        case("", ""),
+        case("f", "MY======"),
+        case("fo", "MZXQ===="),
+        case("foo", "MZXW6==="),
+        case("foob", "MZXW6YQ="),
+        case("fooba", "MZXW6YTB"),
+        case("foobar", "MZXW6YTBOI======"),
+    )]
+    fn will_encode_base32_bytes_correctly(input: &str, expected_result: &str) {
+        let result = super::encode(input.as_bytes(), true);
+        assert_eq!(result, expected_result);
+    }
+
+    #[test]
+    fn will_encode_base32_bytes_without_padding() {
+        let result = super::encode(b"foobar", false);
+        assert_eq!(result, "MZXW6YTBOI");
+    }
+
+    #[rstest(input, expected_result,
+        case("MZXW6YTBOI======", "foobar"),
+        case("MZXW6YTBOI", "foobar"),
+        case("mzxw 6ytb oi", "foobar"),
+    )]
+    fn will_decode_base32_bytes_correctly(input: &str, expected_result: &str) {
+        let result = super::decode(input).unwrap();
+        assert_eq!(result, expected_result.as_bytes());
+    }
+
+    #[test]
+    fn will_reject_invalid_base32_characters() {
+        assert!(super::decode("MZXW1").is_err());
+    }
+}
@@ -0,0 +1,61 @@
+use std::error::Error;
+
+use super::radix_utils;
+
+pub const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+// alphabet used by the python shortuuid library, base58 without the `1`
+pub const BASE57_ALPHABET: &str = "23456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+// arbitrary byte strings, the way bitcoin encodes them. each leading zero byte
+// is kept as a leading '1', since the number alone would lose them
+pub fn encode(bytes: &[u8]) -> String {
+    let zeros = bytes.iter().take_while(|b| **b == 0).count();
+    "1".repeat(zeros) + &radix_utils::encode_bytes(&bytes[zeros..], BASE58_ALPHABET, 0)
+}
+
+pub fn decode(input: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    let zeros = input.chars().take_while(|c| *c == '1').count();
+    // little-endian while decoding, so the number can grow at the end
+    let mut result: Vec<u8> = Vec::with_capacity(input.len());
+
+    for c in input[zeros..].chars() {
+        let mut carry = BASE58_ALPHABET
+            .find(c)
+            .ok_or_else(|| format!("unexpected character: {:?}", c))? as u32;
+        for byte in result.iter_mut() {
+            let value = u32::from(*byte) * 58 + carry;
+            *byte = value as u8;
+            carry = value >> 8;
+        }
+        while carry > 0 {
+            result.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+
+    result.extend(std::iter::repeat_n(0, zeros));
+    result.reverse();
+    Ok(result)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    #[rstest(input, expected_result,
+        case(&[], ""),
+        case(&[0, 0], "11"),
+        case(b"hello world", "StV1DL6CwTryKyV"),
+        case(&[0, 0, 0x28, 0x7f, 0xb4, 0xcd], "11233QC4"),
+    )]
+    fn will_encode_and_decode_byte_strings(input: &[u8], expected_result: &str) {
+        assert_eq!(super::encode(input), expected_result);
+        assert_eq!(super::decode(expected_result).unwrap(), input);
+    }
+
+    #[test]
+    fn will_reject_invalid_characters() {
+        assert!(super::decode("10").is_err());
+    }
+}
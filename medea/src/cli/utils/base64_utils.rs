@@ -36,7 +36,7 @@ fn encode_internal(bytes: &[u8], alphabet: &str, pad: bool) -> String {
             // mask off those 6 bits
             let six_bits = shifted & mask;
             // grab the matching character
-            result.push(alphabet.chars().nth(six_bits as usize).unwrap());
+            result.push(char::from(alphabet.as_bytes()[six_bits as usize]));
         }
 
         // fill any leftover spaces in the 4-character string with padding
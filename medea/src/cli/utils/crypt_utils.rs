@@ -0,0 +1,248 @@
+use digest::Digest;
+use md5::Md5;
+use sha2::{Sha256, Sha512};
+
+// alphabet used by crypt(3) for its base64 variant
+const ITOA64: &[u8] = b"./0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+pub const MD5_MAX_SALT_LENGTH: usize = 8;
+pub const SHA_MAX_SALT_LENGTH: usize = 16;
+pub const SHA_DEFAULT_ROUNDS: u32 = 5000;
+pub const SHA_MIN_ROUNDS: u32 = 1000;
+pub const SHA_MAX_ROUNDS: u32 = 999_999_999;
+
+pub fn is_salt_character(c: char) -> bool {
+    c.is_ascii() && ITOA64.contains(&(c as u8))
+}
+
+pub fn salt_alphabet() -> &'static [u8] {
+    ITOA64
+}
+
+// push the lowest `n` 6-bit groups of `value`, least significant first
+fn push_b64(result: &mut String, mut value: u32, n: usize) {
+    for _ in 0..n {
+        result.push(ITOA64[(value & 0x3F) as usize] as char);
+        value >>= 6;
+    }
+}
+
+fn push_b64_from_bytes(result: &mut String, b2: u8, b1: u8, b0: u8, n: usize) {
+    let value = (u32::from(b2) << 16) | (u32::from(b1) << 8) | u32::from(b0);
+    push_b64(result, value, n);
+}
+
+fn md5_crypt_internal(password: &[u8], salt: &str, magic: &str) -> String {
+    let salt: String = salt.chars().take(MD5_MAX_SALT_LENGTH).collect();
+    let salt_bytes = salt.as_bytes();
+
+    let mut alternate = Md5::new();
+    alternate.update(password);
+    alternate.update(salt_bytes);
+    alternate.update(password);
+    let alternate = alternate.finalize();
+
+    let mut context = Md5::new();
+    context.update(password);
+    context.update(magic.as_bytes());
+    context.update(salt_bytes);
+
+    // add one byte of the alternate sum for each byte of the password
+    let mut remaining = password.len();
+    while remaining > 0 {
+        let n = remaining.min(16);
+        context.update(&alternate[..n]);
+        remaining -= n;
+    }
+
+    // this is where the original implementation meant to add the alternate
+    // sum, but it ends up adding a null byte instead
+    let mut i = password.len();
+    while i > 0 {
+        if i & 1 == 1 {
+            context.update([0u8]);
+        } else {
+            context.update(&password[..1]);
+        }
+        i >>= 1;
+    }
+
+    let mut result = context.finalize();
+
+    // slow things down
+    for i in 0..1000 {
+        let mut context = Md5::new();
+        if i & 1 == 1 { context.update(password); } else { context.update(result); }
+        if i % 3 != 0 { context.update(salt_bytes); }
+        if i % 7 != 0 { context.update(password); }
+        if i & 1 == 1 { context.update(result); } else { context.update(password); }
+        result = context.finalize();
+    }
+
+    let mut output = format!("{}{}$", magic, salt);
+    for (a, b, c) in [(0, 6, 12), (1, 7, 13), (2, 8, 14), (3, 9, 15), (4, 10, 5)] {
+        push_b64_from_bytes(&mut output, result[a], result[b], result[c], 4);
+    }
+    push_b64_from_bytes(&mut output, 0, 0, result[11], 2);
+
+    output
+}
+
+pub fn md5_crypt(password: &[u8], salt: &str) -> String {
+    md5_crypt_internal(password, salt, "$1$")
+}
+
+pub fn apr1_crypt(password: &[u8], salt: &str) -> String {
+    md5_crypt_internal(password, salt, "$apr1$")
+}
+
+// repeat the bytes of `digest` until `length` bytes have been produced
+fn stretch(digest: &[u8], length: usize) -> Vec<u8> {
+    digest.iter().cycle().take(length).copied().collect()
+}
+
+fn sha_crypt_internal<D: Digest>(
+    password: &[u8],
+    salt: &str,
+    rounds: Option<u32>,
+    magic: &str,
+    byte_order: &[(usize, usize, usize)],
+) -> String {
+    let salt: String = salt.chars().take(SHA_MAX_SALT_LENGTH).collect();
+    let salt_bytes = salt.as_bytes();
+    let round_count = rounds
+        .unwrap_or(SHA_DEFAULT_ROUNDS)
+        .clamp(SHA_MIN_ROUNDS, SHA_MAX_ROUNDS);
+    let digest_size = <D as Digest>::output_size();
+
+    let mut alternate = D::new();
+    alternate.update(password);
+    alternate.update(salt_bytes);
+    alternate.update(password);
+    let alternate = alternate.finalize();
+
+    let mut context = D::new();
+    context.update(password);
+    context.update(salt_bytes);
+
+    let mut remaining = password.len();
+    while remaining > 0 {
+        let n = remaining.min(digest_size);
+        context.update(&alternate[..n]);
+        remaining -= n;
+    }
+
+    let mut i = password.len();
+    while i > 0 {
+        if i & 1 == 1 {
+            context.update(&alternate);
+        } else {
+            context.update(password);
+        }
+        i >>= 1;
+    }
+
+    let mut result = context.finalize().to_vec();
+
+    // sequence P, derived from the password
+    let mut p_context = D::new();
+    for _ in 0..password.len() {
+        p_context.update(password);
+    }
+    let p_bytes = stretch(&p_context.finalize(), password.len());
+
+    // sequence S, derived from the salt
+    let mut s_context = D::new();
+    for _ in 0..(16 + usize::from(result[0])) {
+        s_context.update(salt_bytes);
+    }
+    let s_bytes = stretch(&s_context.finalize(), salt_bytes.len());
+
+    for r in 0..round_count {
+        let mut context = D::new();
+        if r & 1 == 1 { context.update(&p_bytes); } else { context.update(&result); }
+        if r % 3 != 0 { context.update(&s_bytes); }
+        if r % 7 != 0 { context.update(&p_bytes); }
+        if r & 1 == 1 { context.update(&result); } else { context.update(&p_bytes); }
+        result = context.finalize().to_vec();
+    }
+
+    let mut output = String::from(magic);
+    if let Some(r) = rounds {
+        output += &format!("rounds={}$", r.clamp(SHA_MIN_ROUNDS, SHA_MAX_ROUNDS));
+    }
+    output += &salt;
+    output.push('$');
+
+    for &(a, b, c) in byte_order {
+        push_b64_from_bytes(&mut output, result[a], result[b], result[c], 4);
+    }
+    match digest_size {
+        32 => push_b64_from_bytes(&mut output, 0, result[31], result[30], 3),
+        _ => push_b64_from_bytes(&mut output, 0, 0, result[63], 2),
+    }
+
+    output
+}
+
+pub fn sha256_crypt(password: &[u8], salt: &str, rounds: Option<u32>) -> String {
+    let byte_order = [
+        (0, 10, 20), (21, 1, 11), (12, 22, 2), (3, 13, 23), (24, 4, 14),
+        (15, 25, 5), (6, 16, 26), (27, 7, 17), (18, 28, 8), (9, 19, 29),
+    ];
+    sha_crypt_internal::<Sha256>(password, salt, rounds, "$5$", &byte_order)
+}
+
+pub fn sha512_crypt(password: &[u8], salt: &str, rounds: Option<u32>) -> String {
+    let byte_order = [
+        (0, 21, 42), (22, 43, 1), (44, 2, 23), (3, 24, 45), (25, 46, 4),
+        (47, 5, 26), (6, 27, 48), (28, 49, 7), (50, 8, 29), (9, 30, 51),
+        (31, 52, 10), (53, 11, 32), (12, 33, 54), (34, 55, 13), (56, 14, 35),
+        (15, 36, 57), (37, 58, 16), (59, 17, 38), (18, 39, 60), (40, 61, 19),
+        (62, 20, 41),
+    ];
+    sha_crypt_internal::<Sha512>(password, salt, rounds, "$6$", &byte_order)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    #[test]
+    fn will_generate_md5_crypt() {
+        let result = super::md5_crypt(b"password", "saltsalt");
+        assert_eq!(result, "$1$saltsalt$qjXMvbEw8oaL.CzflDtaK/");
+    }
+
+    #[test]
+    fn will_generate_apr1_crypt() {
+        let result = super::apr1_crypt(b"password", "saltsalt");
+        assert_eq!(result, "$apr1$saltsalt$yAAkm4libquA.ZWLHbSBq/");
+    }
+
+    #[test]
+    fn will_truncate_long_md5_salt() {
+        let result = super::md5_crypt(b"password", "saltsaltsalt");
+        assert_eq!(result, "$1$saltsalt$qjXMvbEw8oaL.CzflDtaK/");
+    }
+
+    #[rstest(password, salt, rounds, expected_result,
+        case("password", "saltsaltsaltsalt", None, "$5$saltsaltsaltsalt$WsFBeg1qQ90JL3VkUTuM7xVV/5njhLngIVm6ftSnBR2"),
+        case("Hello world!", "saltstring", None, "$5$saltstring$5B8vYYiY.CVt1RlTTf8KbXBH3hsxY/GNooZaBBGWEc5"),
+        case("Hello world!", "saltstring", Some(10000), "$5$rounds=10000$saltstring$zY4WhW6dya5uGVNotd0y2Lk.E4rItnX94Q0R1OCQl40"),
+    )]
+    fn will_generate_sha256_crypt(password: &str, salt: &str, rounds: Option<u32>, expected_result: &str) {
+        let result = super::sha256_crypt(password.as_bytes(), salt, rounds);
+        assert_eq!(result, expected_result);
+    }
+
+    #[rstest(password, salt, rounds, expected_result,
+        case("password", "abcdefgh", Some(1000), "$6$rounds=1000$abcdefgh$wuAp2XWwaaguzVxZjeM2bd1yLSqbC/I9sr9DFeOfIPoAZiIj3ecL6rf9ibuAg8RDmh1vqbaeL0NSLJtGPF7b60"),
+        case("Hello world!", "saltstring", None, "$6$saltstring$svn8UoSVapNtMuq1ukKS4tPQd8iKwSMHWjl/O817G3uBnIFNjnQJuesI68u4OTLiBFdcbYEdFCoEOfaS35inz1"),
+        case("Hello world!", "saltstring", Some(10), "$6$rounds=1000$saltstring$Zu2Vknok2/f53APfN687ADnzeNBLcsEgTwvcBHMD2./07rZQAt8vsuKVufD15dyZh.LOLB/uZKf6I3GyON4bp/"),
+    )]
+    fn will_generate_sha512_crypt(password: &str, salt: &str, rounds: Option<u32>, expected_result: &str) {
+        let result = super::sha512_crypt(password.as_bytes(), salt, rounds);
+        assert_eq!(result, expected_result);
+    }
+}
@@ -0,0 +1,46 @@
+use std::error::Error;
+
+use serde_json::{Map, Value};
+
+// prints named fields as aligned `key: value` lines, or as a json object
+pub fn format_fields(fields: Vec<(&'static str, Value)>, json: bool) -> Result<String, Box<dyn Error>> {
+    if json {
+        let map: Map<String, Value> = fields
+            .into_iter()
+            .map(|(k, v)| (k.replace(' ', "_"), v))
+            .collect();
+        return Ok(serde_json::to_string_pretty(&Value::Object(map))?);
+    }
+
+    let mut lines: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < fields.len() {
+        let (key, value) = &fields[i];
+        let mut value = match value {
+            Value::String(s) => s.clone(),
+            // list one item per line, lined up under the first one
+            Value::Array(items) => items
+                .iter()
+                .map(|v| match v {
+                    Value::String(s) => s.clone(),
+                    v => v.to_string(),
+                })
+                .collect::<Vec<String>>()
+                .join(&format!("\n{:16}", "")),
+            v => v.to_string(),
+        };
+
+        // fold the names into the line of the value they describe
+        if let Some((next_key, Value::String(next_value))) = fields.get(i + 1) {
+            if *next_key == format!("{} name", key) || *next_key == format!("{} type", key) {
+                value += &format!(" ({})", next_value);
+                i += 1;
+            }
+        }
+
+        lines.push(format!("{:<16}{}", format!("{}:", key), value));
+        i += 1;
+    }
+
+    Ok(lines.join("\n"))
+}
@@ -3,9 +3,10 @@ use digest::{Mac, OutputSizeUser};
 pub trait DynHmacDigest {
     fn update(&mut self, data: &[u8]);
     fn finalize_into_bytes(&mut self) -> Vec<u8>;
+    fn box_clone(&self) -> Box<dyn DynHmacDigest>;
 }
 
-impl<T: Mac + OutputSizeUser + Clone> DynHmacDigest for T {
+impl<T: Mac + OutputSizeUser + Clone + 'static> DynHmacDigest for T {
     fn update(&mut self, data: &[u8]) {
         self.update(data)
     }
@@ -13,4 +14,8 @@ impl<T: Mac + OutputSizeUser + Clone> DynHmacDigest for T {
     fn finalize_into_bytes(&mut self) -> Vec<u8> {
         self.clone().finalize().into_bytes().to_vec().to_owned()
     }
+
+    fn box_clone(&self) -> Box<dyn DynHmacDigest> {
+        Box::new(self.clone())
+    }
 }
\ No newline at end of file
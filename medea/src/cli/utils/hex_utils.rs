@@ -10,13 +10,24 @@ pub fn encode(bytes: &[u8], uppercase: bool) -> String {
         // mask off either bits
         let first = (bytes[i] >> 4) & 0xF;
         let second = bytes[i] & 0xF;
-        result.push(characters.chars().nth(first as usize).unwrap());
-        result.push(characters.chars().nth(second as usize).unwrap());
+        result.push(char::from(characters.as_bytes()[first as usize]));
+        result.push(char::from(characters.as_bytes()[second as usize]));
     }
 
     result
 }
 
+// decode untrusted input, which may have an odd length or stray characters
+pub fn try_decode(input: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    if let Some(c) = input.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(format!("invalid character in hex string: {:?}", c).into());
+    }
+    if !input.len().is_multiple_of(2) {
+        return Err("hex string has an odd number of characters".into());
+    }
+    Ok(decode(input.to_string()))
+}
+
 pub fn decode(input: String) -> Vec<u8> {
     if input.len() == 0 {
         return Vec::new();
@@ -74,4 +85,18 @@ mod tests {
         let result = super::decode(input);
         assert_eq!(result, expected_result);
     }
+
+    #[rstest(input,
+        case("xy!"),
+        case("abc"),
+        case("0g"),
+    )]
+    fn will_reject_invalid_hex(input: &str) {
+        assert!(super::try_decode(input).is_err());
+    }
+
+    #[test]
+    fn will_try_decode_valid_hex() {
+        assert_eq!(super::try_decode("00fF").unwrap(), [0, 255]);
+    }
 }
@@ -0,0 +1,189 @@
+use std::io::{self, Write};
+
+#[derive(Debug, Clone, Copy)]
+pub enum Language {
+    C,
+    Rust,
+    Go,
+    Python,
+    // a plain comma-separated list of decimal bytes
+    DecList,
+}
+
+// writes bytes as a source code literal, wrapping the body so no line is wider
+// than `width` characters. a width of 0 keeps the whole body on one line. the
+// bytes can be written in several calls, so large outputs never need to be
+// held in memory
+pub struct LiteralWriter {
+    language: Language,
+    width: usize,
+    upper: bool,
+    line: String,
+    is_empty_line: bool,
+}
+
+impl LiteralWriter {
+    pub fn new(language: Language, width: usize, upper: bool) -> Self {
+        LiteralWriter {
+            language,
+            width,
+            upper,
+            line: String::new(),
+            is_empty_line: true,
+        }
+    }
+
+    pub fn begin(&self, out: &mut dyn Write, length: u64) -> io::Result<()> {
+        match self.language {
+            Language::C => writeln!(out, "uint8_t data[{}] = {{", length),
+            Language::Rust => writeln!(out, "let data: [u8; {}] = [", length),
+            Language::Go => writeln!(out, "data := []byte{{"),
+            Language::Python => writeln!(out, "data = ("),
+            Language::DecList => Ok(()),
+        }
+    }
+
+    pub fn write(&mut self, out: &mut dyn Write, bytes: &[u8]) -> io::Result<()> {
+        for byte in bytes {
+            let item = self.item(*byte);
+            let separator = self.separator();
+            if !self.is_empty_line
+                && self.width > 0
+                && self.prefix().len() + self.line.len() + separator.len() + item.len() + self.suffix(false).len() > self.width
+            {
+                self.flush_line(out, false)?;
+                out.write_all(b"\n")?;
+            }
+            if !self.is_empty_line {
+                self.line.push_str(separator);
+            }
+            self.line.push_str(&item);
+            self.is_empty_line = false;
+        }
+        Ok(())
+    }
+
+    pub fn finish(&mut self, out: &mut dyn Write) -> io::Result<()> {
+        let footer = match self.language {
+            Language::C => "};",
+            Language::Rust => "];",
+            Language::Go => "}",
+            Language::Python => ")",
+            Language::DecList => "",
+        };
+        // an empty python literal still needs its b"", or it becomes a tuple
+        if !self.is_empty_line || matches!(self.language, Language::Python) {
+            self.flush_line(out, true)?;
+            if !footer.is_empty() {
+                out.write_all(b"\n")?;
+            }
+        }
+        out.write_all(footer.as_bytes())
+    }
+
+    fn item(&self, byte: u8) -> String {
+        match (self.language, self.upper) {
+            (Language::DecList, _) => byte.to_string(),
+            (Language::Python, false) => format!("\\x{:02x}", byte),
+            (Language::Python, true) => format!("\\x{:02X}", byte),
+            (_, false) => format!("0x{:02x}", byte),
+            (_, true) => format!("0x{:02X}", byte),
+        }
+    }
+
+    fn separator(&self) -> &'static str {
+        match self.language {
+            Language::Python => "",
+            _ => ", ",
+        }
+    }
+
+    fn prefix(&self) -> &'static str {
+        match self.language {
+            Language::DecList => "",
+            Language::Python => "    b\"",
+            _ => "    ",
+        }
+    }
+
+    fn suffix(&self, is_last: bool) -> &'static str {
+        match (self.language, is_last) {
+            (Language::DecList, true) => "",
+            (Language::Python, _) => "\"",
+            _ => ",",
+        }
+    }
+
+    fn flush_line(&mut self, out: &mut dyn Write, is_last: bool) -> io::Result<()> {
+        write!(out, "{}{}{}", self.prefix(), self.line, self.suffix(is_last))?;
+        self.line.clear();
+        self.is_empty_line = true;
+        Ok(())
+    }
+}
+
+// formats a whole buffer at once
+pub fn format(language: Language, width: usize, upper: bool, bytes: &[u8]) -> String {
+    let mut writer = LiteralWriter::new(language, width, upper);
+    let mut buffer = Vec::new();
+    // writing to a vec can't fail
+    writer.begin(&mut buffer, bytes.len() as u64).unwrap();
+    writer.write(&mut buffer, bytes).unwrap();
+    writer.finish(&mut buffer).unwrap();
+    // every literal is made of ascii characters
+    String::from_utf8(buffer).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{Language, LiteralWriter};
+
+    #[rstest(language, expected_result,
+        case(Language::C, "uint8_t data[3] = {\n    0x00, 0xab, 0xff,\n};"),
+        case(Language::Rust, "let data: [u8; 3] = [\n    0x00, 0xab, 0xff,\n];"),
+        case(Language::Go, "data := []byte{\n    0x00, 0xab, 0xff,\n}"),
+        case(Language::Python, "data = (\n    b\"\\x00\\xab\\xff\"\n)"),
+        case(Language::DecList, "0, 171, 255"),
+    )]
+    fn will_format_literals(language: Language, expected_result: &str) {
+        assert_eq!(super::format(language, 80, false, &[0, 171, 255]), expected_result);
+    }
+
+    #[rstest(language, width, expected_result,
+        case(Language::C, 20, "uint8_t data[5] = {\n    0x01, 0x02,\n    0x03, 0x04,\n    0x05,\n};"),
+        case(Language::Python, 16, "data = (\n    b\"\\x01\\x02\"\n    b\"\\x03\\x04\"\n    b\"\\x05\"\n)"),
+        case(Language::DecList, 5, "1, 2,\n3, 4,\n5"),
+        case(Language::DecList, 0, "1, 2, 3, 4, 5"),
+    )]
+    fn will_wrap_long_literals(language: Language, width: usize, expected_result: &str) {
+        assert_eq!(super::format(language, width, false, &[1, 2, 3, 4, 5]), expected_result);
+    }
+
+    #[test]
+    fn will_keep_at_least_one_byte_per_line() {
+        assert_eq!(super::format(Language::DecList, 1, false, &[100, 200]), "100,\n200");
+    }
+
+    #[test]
+    fn will_format_empty_python_literal_as_bytes() {
+        assert_eq!(super::format(Language::Python, 80, false, &[]), "data = (\n    b\"\"\n)");
+    }
+
+    #[test]
+    fn will_use_upper_case_hex() {
+        assert_eq!(super::format(Language::Go, 80, true, &[0xab]), "data := []byte{\n    0xAB,\n}");
+    }
+
+    #[test]
+    fn will_write_in_several_calls() {
+        let mut writer = LiteralWriter::new(Language::Rust, 20, false);
+        let mut buffer = Vec::new();
+        writer.begin(&mut buffer, 5).unwrap();
+        writer.write(&mut buffer, &[1, 2, 3]).unwrap();
+        writer.write(&mut buffer, &[4, 5]).unwrap();
+        writer.finish(&mut buffer).unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap(), super::format(Language::Rust, 20, false, &[1, 2, 3, 4, 5]));
+    }
+}
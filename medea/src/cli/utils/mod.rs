@@ -1,4 +1,12 @@
 pub mod base64_utils;
 pub mod hex_utils;
 pub mod hash_utils;
-pub mod ascii_utils;
\ No newline at end of file
+pub mod ascii_utils;
+pub mod crypt_utils;
+pub mod base32_utils;
+pub mod base58_utils;
+pub mod size_utils;
+pub mod regex_utils;
+pub mod literal_utils;
+pub mod radix_utils;
+pub mod field_utils;
\ No newline at end of file
@@ -0,0 +1,140 @@
+use std::error::Error;
+
+// numbers written in any alphabet, most significant digit first. the first
+// character of the alphabet is the zero digit, and outputs are padded with it
+// up to `width`
+pub fn encode_u128(mut value: u128, alphabet: &str, width: usize) -> String {
+    let alphabet = alphabet.as_bytes();
+    let base = alphabet.len() as u128;
+    let mut result = Vec::with_capacity(width);
+
+    while value > 0 {
+        result.push(alphabet[(value % base) as usize]);
+        value /= base;
+    }
+
+    // pad with the zero digit
+    while result.len() < width {
+        result.push(alphabet[0]);
+    }
+
+    result.reverse();
+    result.into_iter().map(|b| b as char).collect()
+}
+
+pub fn decode_u128(input: &str, alphabet: &str) -> Result<u128, Box<dyn Error>> {
+    let base = alphabet.len() as u128;
+    let mut value = 0u128;
+
+    for c in input.chars() {
+        let digit = alphabet
+            .find(c)
+            .ok_or_else(|| format!("unexpected character: {:?}", c))?;
+        value = value
+            .checked_mul(base)
+            .and_then(|v| v.checked_add(digit as u128))
+            .ok_or("value is too large")?;
+    }
+
+    Ok(value)
+}
+
+// big-endian byte strings too wide for a u128, such as 160 bit ksuids
+pub fn encode_bytes(bytes: &[u8], alphabet: &str, width: usize) -> String {
+    let alphabet = alphabet.as_bytes();
+    let base = alphabet.len() as u32;
+    let mut number = bytes.to_vec();
+    let mut result = Vec::with_capacity(width);
+
+    while number.iter().any(|b| *b != 0) {
+        // long division of the whole number by the base
+        let mut remainder = 0u32;
+        for byte in number.iter_mut() {
+            let value = (remainder << 8) | u32::from(*byte);
+            *byte = (value / base) as u8;
+            remainder = value % base;
+        }
+        result.push(alphabet[remainder as usize]);
+    }
+
+    while result.len() < width {
+        result.push(alphabet[0]);
+    }
+
+    result.reverse();
+    result.into_iter().map(|b| b as char).collect()
+}
+
+pub fn decode_bytes(input: &str, alphabet: &str, length: usize) -> Result<Vec<u8>, Box<dyn Error>> {
+    let base = alphabet.len() as u32;
+    let mut result = vec![0u8; length];
+
+    for c in input.chars() {
+        let mut carry = alphabet
+            .find(c)
+            .ok_or_else(|| format!("unexpected character: {:?}", c))? as u32;
+        for byte in result.iter_mut().rev() {
+            let value = u32::from(*byte) * base + carry;
+            *byte = value as u8;
+            carry = value >> 8;
+        }
+        if carry != 0 {
+            return Err("value is too large".into());
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::cli::utils::base58_utils::{BASE57_ALPHABET, BASE58_ALPHABET};
+
+    #[rstest(value, alphabet, width, expected_result,
+        case(0, BASE58_ALPHABET, 0, ""),
+        case(0, BASE58_ALPHABET, 3, "111"),
+        case(57, BASE58_ALPHABET, 0, "z"),
+        case(58, BASE58_ALPHABET, 0, "21"),
+        case(0x6ba7b8109dad11d180b400c04fd430c8, BASE57_ALPHABET, 22, "MAnkyno2VCnFzuVMWtxBda"),
+    )]
+    fn will_encode_u128(value: u128, alphabet: &str, width: usize, expected_result: &str) {
+        let result = super::encode_u128(value, alphabet, width);
+        assert_eq!(result, expected_result);
+    }
+
+    #[rstest(input, alphabet, expected_result,
+        case("", BASE58_ALPHABET, 0),
+        case("1121", BASE58_ALPHABET, 58),
+        case("MAnkyno2VCnFzuVMWtxBda", BASE57_ALPHABET, 0x6ba7b8109dad11d180b400c04fd430c8),
+    )]
+    fn will_decode_u128(input: &str, alphabet: &str, expected_result: u128) {
+        let result = super::decode_u128(input, alphabet).unwrap();
+        assert_eq!(result, expected_result);
+    }
+
+    #[test]
+    fn will_round_trip_bytes() {
+        let bytes = [0x00, 0x6b, 0xa7, 0xb8, 0x10, 0x9d, 0xad, 0x11, 0xd1, 0x80, 0xb4, 0x00, 0xc0, 0x4f, 0xd4, 0x30, 0xc8, 0xff, 0x00, 0x01];
+        let encoded = super::encode_bytes(&bytes, BASE58_ALPHABET, 0);
+        assert_eq!(super::decode_bytes(&encoded, BASE58_ALPHABET, 20).unwrap(), bytes);
+    }
+
+    #[test]
+    fn will_encode_bytes_like_u128() {
+        let value = 0x6ba7b8109dad11d180b400c04fd430c8u128;
+        let result = super::encode_bytes(&value.to_be_bytes(), BASE57_ALPHABET, 22);
+        assert_eq!(result, super::encode_u128(value, BASE57_ALPHABET, 22));
+    }
+
+    #[test]
+    fn will_reject_invalid_characters() {
+        assert!(super::decode_u128("10", BASE58_ALPHABET).is_err());
+    }
+
+    #[test]
+    fn will_reject_overflow() {
+        assert!(super::decode_u128(&"z".repeat(30), BASE58_ALPHABET).is_err());
+    }
+}
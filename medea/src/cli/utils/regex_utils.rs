@@ -0,0 +1,218 @@
+use std::error::Error;
+
+use rand::Rng;
+use regex::Regex;
+use regex_syntax::hir::{Class, ClassUnicode, ClassUnicodeRange, Hir, HirKind, Look};
+
+// generates random strings that match a regular expression, by walking the
+// pattern's high level intermediate representation from regex-syntax
+pub struct RegexGenerator {
+    hir: Hir,
+    max_repeat: u32,
+    unicode: bool,
+    // anchors are generated as nothing, so the output is checked against the
+    // whole pattern to catch ones that can't be satisfied, like `a^b`
+    matcher: Regex,
+}
+
+impl RegexGenerator {
+    // unbounded repetitions like `*`, `+` and `{n,}` repeat at most
+    // `max_repeat` more times than their minimum. classes like `.` or `\d`
+    // match far more than ascii, so unless `unicode` is set, characters are
+    // drawn from the printable ascii part of a class whenever it has one
+    pub fn new(pattern: &str, max_repeat: u32, unicode: bool) -> Result<Self, Box<dyn Error>> {
+        let hir = regex_syntax::Parser::new()
+            .parse(pattern)
+            .map_err(|e| format!("invalid regex: {}", e))?;
+        check_supported(&hir)?;
+        let matcher = Regex::new(&format!("^(?:{})$", pattern))?;
+        Ok(RegexGenerator { hir, max_repeat, unicode, matcher })
+    }
+
+    pub fn generate<R: Rng>(&self, rng: &mut R) -> Result<String, Box<dyn Error>> {
+        let mut output = String::new();
+        self.push(&self.hir, rng, &mut output)?;
+        if !self.matcher.is_match(&output) {
+            return Err("pattern can't be generated, anchors can only appear at the start or end of a line".into());
+        }
+        Ok(output)
+    }
+
+    fn push<R: Rng>(&self, hir: &Hir, rng: &mut R, output: &mut String) -> Result<(), Box<dyn Error>> {
+        match hir.kind() {
+            HirKind::Empty | HirKind::Look(_) => {},
+            HirKind::Literal(literal) => output.push_str(std::str::from_utf8(&literal.0)?),
+            HirKind::Class(Class::Unicode(class)) => {
+                let class = match self.unicode {
+                    true => class.clone(),
+                    false => printable_ascii(class),
+                };
+                let total: u32 = class.ranges().iter().map(range_size).sum();
+                if total == 0 {
+                    return Err("pattern contains a character class that can't match anything".into());
+                }
+                let mut index = rng.gen_range(0..total);
+                for range in class.ranges() {
+                    let size = range_size(range);
+                    if index < size {
+                        let mut code_point = range.start() as u32 + index;
+                        if spans_surrogates(range) && code_point >= SURROGATES_START {
+                            code_point += SURROGATES_LENGTH;
+                        }
+                        output.push(char::from_u32(code_point).ok_or("invalid character in class")?);
+                        break;
+                    }
+                    index -= size;
+                }
+            },
+            HirKind::Class(Class::Bytes(class)) => {
+                let total: u32 = class.ranges().iter().map(|r| u32::from(r.end() - r.start()) + 1).sum();
+                if total == 0 {
+                    return Err("pattern contains a character class that can't match anything".into());
+                }
+                let mut index = rng.gen_range(0..total);
+                for range in class.ranges() {
+                    let size = u32::from(range.end() - range.start()) + 1;
+                    if index < size {
+                        // utf-8 mode only allows ascii byte classes
+                        output.push(char::from(range.start() + index as u8));
+                        break;
+                    }
+                    index -= size;
+                }
+            },
+            HirKind::Repetition(repetition) => {
+                let max = repetition.max.unwrap_or(repetition.min.saturating_add(self.max_repeat));
+                for _ in 0..rng.gen_range(repetition.min..=max) {
+                    self.push(&repetition.sub, rng, output)?;
+                }
+            },
+            HirKind::Capture(capture) => self.push(&capture.sub, rng, output)?,
+            HirKind::Concat(hirs) => {
+                for hir in hirs {
+                    self.push(hir, rng, output)?;
+                }
+            },
+            HirKind::Alternation(hirs) => self.push(&hirs[rng.gen_range(0..hirs.len() as u32) as usize], rng, output)?,
+        }
+        Ok(())
+    }
+}
+
+// surrogate code points are not characters, but a class range like the one
+// behind `.` runs straight over them
+const SURROGATES_START: u32 = 0xD800;
+const SURROGATES_LENGTH: u32 = 0x800;
+
+fn spans_surrogates(range: &ClassUnicodeRange) -> bool {
+    (range.start() as u32) < SURROGATES_START && (range.end() as u32) >= SURROGATES_START + SURROGATES_LENGTH
+}
+
+fn range_size(range: &ClassUnicodeRange) -> u32 {
+    let size = range.end() as u32 - range.start() as u32 + 1;
+    match spans_surrogates(range) {
+        true => size - SURROGATES_LENGTH,
+        false => size,
+    }
+}
+
+fn printable_ascii(class: &ClassUnicode) -> ClassUnicode {
+    let mut ascii = ClassUnicode::new([ClassUnicodeRange::new(' ', '~')]);
+    ascii.intersect(class);
+    match ascii.ranges().is_empty() {
+        true => class.clone(),
+        false => ascii,
+    }
+}
+
+fn check_supported(hir: &Hir) -> Result<(), Box<dyn Error>> {
+    match hir.kind() {
+        HirKind::Look(look) => match look {
+            Look::Start | Look::End | Look::StartLF | Look::EndLF | Look::StartCRLF | Look::EndCRLF => Ok(()),
+            _ => Err(format!("word boundary assertions are not supported, found {:?}", look).into()),
+        },
+        HirKind::Repetition(repetition) => check_supported(&repetition.sub),
+        HirKind::Capture(capture) => check_supported(&capture.sub),
+        HirKind::Concat(hirs) | HirKind::Alternation(hirs) => hirs.iter().try_for_each(check_supported),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+    use regex::Regex;
+    use rstest::rstest;
+
+    use super::RegexGenerator;
+
+    #[rstest(pattern,
+        case(r"[A-Z]{3}-\d{4}"),
+        case(r"^(foo|bar)+baz?$"),
+        case(r"(?i)hello [a-f0-9]{8}"),
+        case(r"[^a-z\s]{5,10}"),
+        case(r"\p{Greek}{4}"),
+        case(r"(?-u)[\x00-\x7f]*"),
+        case(r""),
+    )]
+    fn will_generate_matching_strings(pattern: &str) {
+        let sut = RegexGenerator::new(pattern, 8, false).unwrap();
+        let matcher = Regex::new(&format!("^(?:{})$", pattern)).unwrap();
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        for _ in 0..100 {
+            let result = sut.generate(&mut rng).unwrap();
+            assert!(matcher.is_match(&result), "{:?}", result);
+        }
+    }
+
+    #[rstest(pattern, unicode, expected_ascii,
+        case(r"\d{8}", false, true),
+        case(r".{8}", false, true),
+        case(r"\p{Greek}", false, false),
+        case(r"\d{8}", true, false),
+    )]
+    fn will_prefer_printable_ascii(pattern: &str, unicode: bool, expected_ascii: bool) {
+        let sut = RegexGenerator::new(pattern, 8, unicode).unwrap();
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let results: Vec<String> = (0..100).map(|_| sut.generate(&mut rng).unwrap()).collect();
+        assert_eq!(results.iter().all(|r| r.chars().all(|c| (' '..='~').contains(&c))), expected_ascii);
+    }
+
+    #[test]
+    fn will_skip_surrogates_in_unicode_classes() {
+        let sut = RegexGenerator::new(".{200}", 8, true).unwrap();
+        let matcher = Regex::new("^.{200}$").unwrap();
+        for seed in 0..20 {
+            let mut rng = ChaCha20Rng::seed_from_u64(seed);
+            let result = sut.generate(&mut rng).unwrap();
+            assert!(matcher.is_match(&result));
+        }
+    }
+
+    #[test]
+    fn will_cap_unbounded_repetition() {
+        let sut = RegexGenerator::new("a*b+", 3, false).unwrap();
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        for _ in 0..100 {
+            let result = sut.generate(&mut rng).unwrap();
+            assert!(result.matches('a').count() <= 3);
+            assert!((1..=4).contains(&result.matches('b').count()));
+        }
+    }
+
+    #[rstest(pattern, expected_error,
+        case(r"foo(?=bar)", "look-around"),
+        case(r"(?<!foo)bar", "look-around"),
+        case(r"\bfoo", "word boundary"),
+        case(r"(a", "invalid regex"),
+        case(r"a^b", "anchors"),
+        case(r"[^\s\S]", "can't match anything"),
+    )]
+    fn will_reject_unsupported_patterns(pattern: &str, expected_error: &str) {
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let result = RegexGenerator::new(pattern, 8, false).and_then(|sut| sut.generate(&mut rng));
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains(expected_error), "{}", error);
+    }
+}
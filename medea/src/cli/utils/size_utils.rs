@@ -0,0 +1,58 @@
+// parses counts like `10000`, `10k`, `10M` or `1Gi`. single letter suffixes
+// are powers of 1000, and suffixes ending in `i` are powers of 1024
+pub fn parse_size(input: &str) -> Result<u64, String> {
+    let input = input.trim();
+    let split = input.find(|c: char| !c.is_ascii_digit()).unwrap_or(input.len());
+    let (digits, suffix) = input.split_at(split);
+    if digits.is_empty() {
+        return Err(format!("expected a number, found {:?}", input));
+    }
+
+    let multiplier: u64 = match suffix.trim_end_matches(['b', 'B']) {
+        "" => 1,
+        "k" | "K" => 1_000,
+        "M" => 1_000_000,
+        "G" => 1_000_000_000,
+        "T" => 1_000_000_000_000,
+        "Ki" => 1 << 10,
+        "Mi" => 1 << 20,
+        "Gi" => 1 << 30,
+        "Ti" => 1 << 40,
+        _ => return Err(format!("unknown size suffix {:?}, expected one of k, M, G, T, Ki, Mi, Gi or Ti", suffix)),
+    };
+
+    digits
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(multiplier))
+        .ok_or_else(|| format!("{:?} is too large", input))
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    #[rstest(input, expected_result,
+        case("0", 0),
+        case("123", 123),
+        case("10k", 10_000),
+        case("10M", 10_000_000),
+        case("10G", 10_000_000_000),
+        case("1Gi", 1_073_741_824),
+        case("4KiB", 4096),
+    )]
+    fn will_parse_size(input: &str, expected_result: u64) {
+        assert_eq!(super::parse_size(input).unwrap(), expected_result);
+    }
+
+    #[rstest(input,
+        case(""),
+        case("G"),
+        case("10X"),
+        case("1.5G"),
+        case("100000000T"),
+    )]
+    fn will_reject_invalid_size(input: &str) {
+        assert!(super::parse_size(input).is_err());
+    }
+}